@@ -0,0 +1,117 @@
+extern crate aoc;
+
+use std::env;
+use std::fs;
+use std::process;
+use std::time::Instant;
+
+use aoc::cache::{self, Cache};
+use aoc::*;
+
+const USAGE: &str = "Usage: run [day...]\nSet AOC_CACHE_DIR to reuse answers for unchanged inputs";
+
+// (answer, whether it came from the cache)
+fn answer<T: ToString, F: FnOnce() -> T>(cache: &Option<Cache>, day: usize, part: usize, input: &str, solve: F) -> (String, bool) {
+    match *cache {
+        Some(ref cache) => match cache.cached_answer(day, part, input) {
+            Some(answer) => (answer, true),
+            None => {
+                let (answer, stored) = cache.answer(day, part, input, solve);
+                if let Err(e) = stored {
+                    eprintln!("Could not cache day {} part {}: {}", day, part, e);
+                }
+
+                (answer, false)
+            }
+        },
+        None => (solve().to_string(), false)
+    }
+}
+
+fn report(day: usize, part: usize, (answer, cached): (String, bool), started: Instant) {
+    let timing = if cached {
+        "cached".to_string()
+    } else {
+        format!("{} ms", started.elapsed().as_millis())
+    };
+
+    println!("Day {} part {} ({}): {}", day, part, timing, answer);
+}
+
+// Each part generates its own input, both because some solvers (day 10)
+// advance the parsed state in place, and so that cached parts skip parsing
+macro_rules! parts {
+    ($cache:expr, $day:expr, $input:expr, $generator:expr, $part1:expr) => {{
+        let started = Instant::now();
+        let result = answer($cache, $day, 1, $input, || $part1(&$generator($input)));
+        report($day, 1, result, started);
+    }};
+    ($cache:expr, $day:expr, $input:expr, $generator:expr, $part1:expr, $part2:expr) => {{
+        parts!($cache, $day, $input, $generator, $part1);
+
+        let started = Instant::now();
+        let result = answer($cache, $day, 2, $input, || $part2(&$generator($input)));
+        report($day, 2, result, started);
+    }};
+}
+
+fn text(input: &str) -> &str {
+    input
+}
+
+fn run(cache: &Option<Cache>, day: usize, input: &str) {
+    match day {
+        1 => parts!(cache, day, input, day1::input_generator, day1::solve_part1, day1::solve_part2),
+        2 => parts!(cache, day, input, day2::input_generator, day2::solve_part1, day2::solve_part2),
+        3 => parts!(cache, day, input, day3::input_generator, day3::solve_part1, day3::solve_part2),
+        4 => parts!(cache, day, input, day4::input_generator, day4::solve_part1, day4::solve_part2),
+        5 => parts!(cache, day, input, text, day5::solve_part1, day5::solve_part2),
+        6 => parts!(cache, day, input, day6::input_generator, day6::solve_part1, day6::solve_part2),
+        7 => parts!(cache, day, input, day7::input_generator, day7::solve_part1, day7::solve_part2),
+        8 => parts!(cache, day, input, day8::input_generator, day8::solve_part1, day8::solve_part2),
+        9 => parts!(cache, day, input, day9::input_generator, day9::solve_part1, day9::solve_part2),
+        10 => parts!(cache, day, input, day10::input_generator, day10::solve_part1, day10::solve_part2),
+        11 => parts!(cache, day, input, text, day11::solve_part1, day11::solve_part2),
+        12 => parts!(cache, day, input, day12::input_generator, day12::solve_part1, day12::solve_part2),
+        13 => parts!(cache, day, input, day13::input_generator, day13::solve_part1, day13::solve_part2),
+        14 => parts!(cache, day, input, day14::input_generator, day14::solve_part1, day14::solve_part2),
+        15 => parts!(cache, day, input, day15::input_generator, day15::solve_part1, day15::solve_part2),
+        16 => parts!(cache, day, input, day16::input_generator, day16::solve_part1, day16::solve_part2),
+        17 => parts!(cache, day, input, day17::input_generator, day17::solve_part1, day17::solve_part2),
+        18 => parts!(cache, day, input, day18::input_generator, day18::solve_part1, day18::solve_part2),
+        19 => parts!(cache, day, input, day19::input_generator, day19::solve_part1, day19::solve_part2),
+        20 => parts!(cache, day, input, text, day20::solve_part1, day20::solve_part2),
+        21 => parts!(cache, day, input, day21::input_generator_wrapper, day21::solve_part1, day21::solve_part2),
+        22 => parts!(cache, day, input, day22::input_generator, day22::solve_part1, day22::solve_part2),
+        23 => parts!(cache, day, input, day23::input_generator, day23::solve_part1, day23::solve_part2),
+        24 => parts!(cache, day, input, day24::input_generator, day24::solve_part1, day24::solve_part2),
+        25 => parts!(cache, day, input, day25::input_generator, day25::solve_part1),
+        _ => { eprintln!("{}", USAGE); process::exit(1) }
+    }
+}
+
+fn main() {
+    let parsed: Result<Vec<usize>, _> = env::args().skip(1).map(|d| d.parse::<usize>()).collect();
+    let mut days = match parsed {
+        Ok(days) => days,
+        Err(_) => { eprintln!("{}", USAGE); process::exit(1) }
+    };
+
+    if days.is_empty() { days = (1..=25).collect() }
+
+    let cache = Cache::from_env();
+    if env::var_os(cache::ENV_VAR).is_some() && cache.is_none() {
+        eprintln!("Could not open the cache directory, running without it");
+    }
+
+    for day in days {
+        let path = format!("input/2018/day{}.txt", day);
+        let raw = match fs::read_to_string(&path) {
+            Ok(raw) => raw,
+            Err(e) => { eprintln!("Could not read {}: {}", path, e); continue }
+        };
+
+        // cargo-aoc trims input, so the generators expect it
+        run(&cache, day, raw.trim());
+    }
+}
@@ -0,0 +1,290 @@
+extern crate aoc;
+extern crate crossterm;
+
+use std::env;
+use std::fs;
+use std::io::{self, Write};
+use std::process;
+
+use aoc::{day13, day15, day17, day18};
+use aoc::simulation::Simulation;
+use crossterm::{cursor, execute, queue, terminal};
+use crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use crossterm::style::{Attribute, Print, SetAttribute};
+
+const USAGE: &str = "Usage: viewer <13|15|17|18> [input file] [elf power]";
+const HELP: &str = "n: next  p: previous  g: go to step  arrows/hjkl: move  q: quit";
+// Only every this many steps is a state kept, the ones in between are stepped
+// to again from the closest kept one before them
+const CHECKPOINT_INTERVAL: usize = 100;
+
+trait Viewable: Simulation + Clone {
+    fn steps(&self) -> usize;
+    fn render(&self) -> String;
+    fn inspect(&self, y: usize, x: usize) -> String;
+    fn start(&self) -> (usize, usize) { (0, 0) }
+}
+
+impl Viewable for day13::Tracks {
+    fn steps(&self) -> usize { self.ticks() }
+    fn render(&self) -> String { day13::Tracks::render(self) }
+
+    fn inspect(&self, y: usize, x: usize) -> String {
+        let crash = match self.first_crash() {
+            Some((y, x)) => format!(" first crash at {},{}", x, y),
+            None => "".to_string()
+        };
+
+        match (self.elf_at(y, x), self.piece_at(y, x)) {
+            (Some(elf), _) => format!("{:?}{}", elf, crash),
+            (None, Some(piece)) => format!("{:?}{}", piece, crash),
+            (None, None) => crash
+        }
+    }
+}
+
+impl Viewable for day15::Battle {
+    fn steps(&self) -> usize { self.rounds() }
+    fn render(&self) -> String { day15::Battle::render(self) }
+
+    fn inspect(&self, y: usize, x: usize) -> String {
+        let outcome = match self.outcome() {
+            Some((no_losses, score)) => format!(" outcome {} (no elf losses: {})", score, no_losses),
+            None => "".to_string()
+        };
+
+        match self.square_at(y, x) {
+            Some(square) => format!("{:?}{}", square, outcome),
+            None => outcome
+        }
+    }
+}
+
+impl Viewable for day17::Reservoir {
+    fn steps(&self) -> usize { self.drips() }
+    fn render(&self) -> String { day17::Reservoir::render(self) }
+    fn start(&self) -> (usize, usize) { (0, 500) }
+
+    fn inspect(&self, y: usize, x: usize) -> String {
+        match self.tile_at(y, x) {
+            Some(tile) => format!("{:?}", tile),
+            None => "".to_string()
+        }
+    }
+}
+
+impl Viewable for day18::Landscape {
+    fn steps(&self) -> usize { self.minute() }
+    fn render(&self) -> String { day18::Landscape::render(self) }
+
+    fn inspect(&self, y: usize, x: usize) -> String {
+        match self.tile_at(y, x) {
+            Some(tile) => format!("{:?} resource value {}", tile, self.resource_value()),
+            None => "".to_string()
+        }
+    }
+}
+
+struct Viewer<T: Viewable> {
+    // The states after 0, CHECKPOINT_INTERVAL, 2 * CHECKPOINT_INTERVAL, ...
+    // steps, as far as the viewer has been
+    checkpoints: Vec<T>,
+    current: T,
+    // Number of steps from the initial state to the current one
+    position: usize,
+    cursor: (usize, usize),
+    prompt: Option<String>
+}
+
+impl<T: Viewable> Viewer<T> {
+    fn new(initial: T) -> Viewer<T> {
+        let cursor = initial.start();
+        Viewer { checkpoints: vec![initial.clone()], current: initial, position: 0, cursor, prompt: None }
+    }
+
+    fn state(&self) -> &T {
+        &self.current
+    }
+
+    fn restore(&mut self, checkpoint: usize) {
+        self.current = self.checkpoints[checkpoint].clone();
+        self.position = checkpoint * CHECKPOINT_INTERVAL;
+    }
+
+    fn forward(&mut self) -> bool {
+        if self.current.is_finished() { return false }
+
+        self.current.step();
+        self.position += 1;
+
+        if self.position == self.checkpoints.len() * CHECKPOINT_INTERVAL {
+            self.checkpoints.push(self.current.clone());
+        }
+
+        true
+    }
+
+    fn backward(&mut self) {
+        if self.position == 0 { return }
+
+        let target = self.position - 1;
+        self.restore(target / CHECKPOINT_INTERVAL);
+
+        while self.position < target {
+            self.forward();
+        }
+    }
+
+    fn jump(&mut self, step: usize) {
+        // Start from the last checkpoint not past the step, unless the
+        // current state is already between it and the step
+        let checkpoint = self.checkpoints.iter().rposition(|state| state.steps() <= step).unwrap_or(0);
+        if self.current.steps() > step || checkpoint * CHECKPOINT_INTERVAL > self.position {
+            self.restore(checkpoint);
+        }
+
+        while self.current.steps() < step {
+            if !self.forward() { break }
+        }
+    }
+
+    fn move_cursor(&mut self, dy: isize, dx: isize) {
+        let (y, x) = self.cursor;
+        let ny = y as isize + dy;
+        let nx = x as isize + dx;
+
+        if ny >= 0 && nx >= 0 {
+            self.cursor = (ny as usize, nx as usize);
+        }
+    }
+
+    fn draw<W: Write>(&self, out: &mut W) -> io::Result<()> {
+        let (columns, rows) = terminal::size()?;
+        let (width, height) = (columns as usize, (rows as usize).saturating_sub(2));
+        let (y, x) = self.cursor;
+        let top = y.saturating_sub(height / 2);
+        let left = x.saturating_sub(width / 2);
+
+        queue!(out, terminal::Clear(terminal::ClearType::All))?;
+
+        let rendered = self.state().render();
+
+        for (row, line) in rendered.lines().skip(top).take(height).enumerate() {
+            queue!(out, cursor::MoveTo(0, row as u16))?;
+
+            for (column, c) in line.chars().skip(left).take(width).enumerate() {
+                if (top + row, left + column) == self.cursor {
+                    queue!(out, SetAttribute(Attribute::Reverse), Print(c), SetAttribute(Attribute::Reset))?;
+                } else {
+                    queue!(out, Print(c))?;
+                }
+            }
+        }
+
+        let state = self.state();
+        let status = format!(
+            "step {}{} | {},{} | {}",
+            state.steps(),
+            if state.is_finished() { " (finished)" } else { "" },
+            x, y,
+            state.inspect(y, x)
+        );
+        let footer = match self.prompt {
+            Some(ref digits) => format!("go to step: {}", digits),
+            None => HELP.to_string()
+        };
+
+        queue!(
+            out,
+            cursor::MoveTo(0, rows.saturating_sub(2)), Print(status),
+            cursor::MoveTo(0, rows.saturating_sub(1)), Print(footer)
+        )?;
+
+        out.flush()
+    }
+
+    // Returns false when the viewer should quit
+    fn handle(&mut self, code: KeyCode) -> bool {
+        if let Some(mut digits) = self.prompt.take() {
+            match code {
+                KeyCode::Char(c) if c.is_ascii_digit() => { digits.push(c); self.prompt = Some(digits) },
+                KeyCode::Backspace => { digits.pop(); self.prompt = Some(digits) },
+                KeyCode::Enter => if let Ok(step) = digits.parse::<usize>() { self.jump(step) },
+                _ => ()
+            }
+
+            return true;
+        }
+
+        match code {
+            KeyCode::Char('q') | KeyCode::Esc => return false,
+            KeyCode::Char('n') => { self.forward(); },
+            KeyCode::Char('p') => self.backward(),
+            KeyCode::Char('g') => self.prompt = Some(String::new()),
+            KeyCode::Up | KeyCode::Char('k') => self.move_cursor(-1, 0),
+            KeyCode::Down | KeyCode::Char('j') => self.move_cursor(1, 0),
+            KeyCode::Left | KeyCode::Char('h') => self.move_cursor(0, -1),
+            KeyCode::Right | KeyCode::Char('l') => self.move_cursor(0, 1),
+            _ => ()
+        }
+
+        true
+    }
+}
+
+fn run<T: Viewable>(initial: T) -> io::Result<()> {
+    let mut viewer = Viewer::new(initial);
+    let mut out = io::stdout();
+
+    terminal::enable_raw_mode()?;
+    execute!(out, terminal::EnterAlternateScreen, cursor::Hide)?;
+
+    let result = (|| -> io::Result<()> {
+        loop {
+            viewer.draw(&mut out)?;
+
+            if let Event::Key(key) = event::read()? {
+                if key.kind != KeyEventKind::Press { continue }
+                if !viewer.handle(key.code) { return Ok(()) }
+            }
+        }
+    })();
+
+    execute!(out, cursor::Show, terminal::LeaveAlternateScreen)?;
+    terminal::disable_raw_mode()?;
+
+    result
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+
+    let day = match args.get(1).and_then(|d| d.parse::<usize>().ok()) {
+        Some(day) => day,
+        None => { eprintln!("{}", USAGE); process::exit(1) }
+    };
+
+    let path = args.get(2).cloned().unwrap_or(format!("input/2018/day{}.txt", day));
+    let raw = match fs::read_to_string(&path) {
+        Ok(raw) => raw,
+        Err(e) => { eprintln!("Could not read {}: {}", path, e); process::exit(1) }
+    };
+    // cargo-aoc trims input, so the generators expect it
+    let input = raw.trim();
+
+    let result = match day {
+        13 => run(day13::Tracks::new(&day13::input_generator(input))),
+        15 => {
+            let elf_power = args.get(3).and_then(|p| p.parse::<isize>().ok()).unwrap_or(3);
+            run(day15::Battle::new(&day15::input_generator(input), elf_power))
+        },
+        17 => run(day17::Reservoir::new(&day17::input_generator(input))),
+        18 => run(day18::Landscape::new(&day18::input_generator(input))),
+        _ => { eprintln!("{}", USAGE); process::exit(1) }
+    };
+
+    if let Err(e) = result {
+        eprintln!("{}", e);
+        process::exit(1);
+    }
+}
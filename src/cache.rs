@@ -0,0 +1,102 @@
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process;
+
+// Opt-in cache of answers on disk. Entries are keyed by day and a hash of the
+// raw input, and live in a directory named after the crate version so that a
+// new version never sees stale answers.
+
+pub const ENV_VAR: &str = "AOC_CACHE_DIR";
+
+pub struct Cache {
+    dir: PathBuf
+}
+
+// FNV-1a, chosen over std's hashers because its output is stable across
+// Rust versions and runs
+pub fn input_hash(input: &str) -> u64 {
+    input.bytes().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+impl Cache {
+    pub fn open<P: AsRef<Path>>(base: P) -> io::Result<Cache> {
+        let dir = base.as_ref().join(env!("CARGO_PKG_VERSION"));
+        fs::create_dir_all(&dir)?;
+        Ok(Cache { dir })
+    }
+
+    // Only caches when AOC_CACHE_DIR is set
+    pub fn from_env() -> Option<Cache> {
+        env::var_os(ENV_VAR).and_then(|dir| Cache::open(dir).ok())
+    }
+
+    fn path(&self, day: usize, input: &str, suffix: &str) -> PathBuf {
+        self.dir.join(format!("day{}-{:016x}.{}", day, input_hash(input), suffix))
+    }
+
+    pub fn cached_answer(&self, day: usize, part: usize, input: &str) -> Option<String> {
+        fs::read_to_string(self.path(day, input, &format!("part{}", part))).ok()
+    }
+
+    // Written to a temporary file and renamed into place, so that an
+    // interrupted run never leaves a truncated answer behind
+    pub fn store_answer(&self, day: usize, part: usize, input: &str, answer: &str) -> io::Result<()> {
+        let path = self.path(day, input, &format!("part{}", part));
+        let mut temporary = path.clone().into_os_string();
+        temporary.push(format!(".{}.tmp", process::id()));
+
+        fs::write(&temporary, answer)?;
+        fs::rename(&temporary, &path).inspect_err(|_| { let _ = fs::remove_file(&temporary); })
+    }
+
+    // (answer, result of storing it) with the stored answer if there is one,
+    // otherwise solves and stores it. The answer is returned even if it
+    // couldn't be stored.
+    pub fn answer<T: ToString, F: FnOnce() -> T>(&self, day: usize, part: usize, input: &str, solve: F) -> (String, io::Result<()>) {
+        if let Some(answer) = self.cached_answer(day, part, input) {
+            return (answer, Ok(()));
+        }
+
+        let answer = solve().to_string();
+        let stored = self.store_answer(day, part, input, &answer);
+        (answer, stored)
+    }
+
+    pub fn clear(&self) -> io::Result<()> {
+        fs::remove_dir_all(&self.dir)?;
+        fs::create_dir_all(&self.dir)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{input_hash, Cache};
+    use std::env;
+    use std::fs;
+
+    #[test]
+    fn answers() {
+        let dir = env::temp_dir().join(format!("aoc-cache-test-{}", input_hash(file!())));
+        let cache = Cache::open(&dir).unwrap();
+        cache.clear().unwrap();
+
+        assert_eq!(cache.cached_answer(1, 1, "+1\n+2"), None);
+        assert_eq!(cache.answer(1, 1, "+1\n+2", || 3).0, "3");
+        assert_eq!(cache.answer(1, 1, "+1\n+2", || -> isize { panic!("Should be cached") }).0, "3");
+        assert_eq!(cache.cached_answer(1, 1, "+1\n+3"), None);
+        assert_eq!(cache.cached_answer(1, 2, "+1\n+2"), None);
+
+        // No temporary files are left behind
+        assert_eq!(fs::read_dir(cache.dir.clone()).unwrap().count(), 1);
+
+        // Answers that can't be stored are still returned
+        fs::remove_dir_all(&dir).unwrap();
+        let (answer, stored) = cache.answer(1, 1, "+1\n+2", || 3);
+        assert_eq!(answer, "3");
+        assert!(stored.is_err());
+    }
+}
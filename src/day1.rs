@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 #[aoc_generator(day1)]
 pub fn input_generator(input: &str) -> Vec<isize> {
     input
@@ -15,52 +17,178 @@ pub fn solve_part1(input: &[isize]) -> isize {
 
 #[aoc(day1, part2)]
 pub fn solve_part2(input: &[isize]) -> isize {
-    let mut visited: Vec<bool> = vec![true];
-    let mut visited_neg: Vec<bool> = vec![false];
-    let mut sum = 0;
+    first_repeat(input).expect("Frequency never repeats")
+}
 
-    for i in input.iter().cycle() {
-        sum += i;
+pub fn first_repeat(input: &[isize]) -> Option<isize> {
+    FrequencyReport::new(input.iter().cloned()).first_repeat.map(|repeat| repeat.frequency)
+}
 
-        if sum >= 0 {
-            let pos = sum as usize;
+#[derive(Debug, Clone, PartialEq)]
+pub struct Repeat {
+    pub frequency: isize,
+    // Both count from 0, the frequency repeats after applying input[index]
+    // during that pass
+    pub pass: usize,
+    pub index: usize,
+    // Index of the change in the first pass after which the frequency was
+    // first reached, None for the starting frequency
+    pub matched: Option<usize>
+}
 
-            if visited.len() <= pos {
-                visited.resize(pos + 1, false);
-            }
+#[derive(Debug, Clone, PartialEq)]
+pub struct FrequencyReport {
+    pub changes: usize,
+    pub drift: isize,
+    // Over all frequencies reached until the first repeat, or during one
+    // pass if no frequency ever repeats
+    pub min_frequency: isize,
+    pub max_frequency: isize,
+    pub first_repeat: Option<Repeat>
+}
 
-            if visited[pos] {
-                return sum;
-            }
+impl FrequencyReport {
+    pub fn new<I: IntoIterator<Item = isize>>(changes: I) -> FrequencyReport {
+        // The frequencies of the first pass, starting at 0
+        let mut prefix = vec![0];
 
-            visited[pos] = true;
-        } else {
-            let opposite = (-sum) as usize;
+        for change in changes {
+            let frequency = prefix[prefix.len() - 1] + change;
+            prefix.push(frequency);
+        }
+
+        let drift = prefix.pop().unwrap();
+        let n = prefix.len();
+
+        if n == 0 {
+            return FrequencyReport { changes: 0, drift: 0, min_frequency: 0, max_frequency: 0, first_repeat: None };
+        }
+
+        let repeat = repeat_times(&prefix, drift);
+        let end = repeat.map_or(n, |(time, _)| time);
+
+        // Each frequency moves by the drift every pass, so its extremes are
+        // at the first and the last pass before the end. Those after the end
+        // of a repeat during the first pass are never reached.
+        let mut min_frequency = 0;
+        let mut max_frequency = 0;
+
+        for (i, &frequency) in prefix[..=end.min(n - 1)].iter().enumerate() {
+            let last = frequency + ((end - i) / n) as isize * drift;
+            min_frequency = min_frequency.min(frequency).min(last);
+            max_frequency = max_frequency.max(frequency).max(last);
+        }
+
+        let first_repeat = repeat.map(|(time, matched)| Repeat {
+            frequency: prefix[matched],
+            pass: (time - 1) / n,
+            index: (time - 1) % n,
+            matched: if matched == 0 { None } else { Some(matched - 1) }
+        });
+
+        FrequencyReport { changes: n, drift, min_frequency, max_frequency, first_repeat }
+    }
+}
 
-            if visited_neg.len() <= opposite {
-                visited_neg.resize(opposite + 1, false);
-            }
+// Time 0 is the start and time t the frequency after t changes, counted
+// across passes. Returns the time of the first repeat and the time its
+// frequency was first reached, which is always during the first pass.
+//
+// Every pass shifts each frequency of the first pass by the same drift, so
+// the frequency at time k * n + i is prefix[i] + k * drift. Two prefix sums
+// can only meet in a later pass if they differ by a multiple of the drift,
+// so they are grouped by remainder and only neighbours within a group (in
+// the direction of the drift) need to be considered.
+fn repeat_times(prefix: &[isize], drift: isize) -> Option<(usize, usize)> {
+    let n = prefix.len();
+    let mut seen: HashMap<isize, usize> = HashMap::new();
 
-            if visited_neg[opposite] {
-                return sum;
-            }
+    // Repeats already during the first pass
+    for (time, &frequency) in prefix.iter().enumerate() {
+        if let Some(&earlier) = seen.get(&frequency) {
+            return Some((time, earlier));
+        }
+
+        seen.insert(frequency, time);
+    }
+
+    if drift == 0 { return Some((n, 0)) }
+
+    let mut groups: Vec<(isize, isize, usize)> = prefix
+        .iter()
+        .enumerate()
+        .map(|(i, &frequency)| (frequency.rem_euclid(drift.abs()), frequency, i))
+        .collect();
+    groups.sort();
+
+    let mut first: Option<(usize, usize)> = None;
+
+    for pair in groups.windows(2) {
+        let (remainder1, frequency1, index1) = pair[0];
+        let (remainder2, frequency2, index2) = pair[1];
+        if remainder1 != remainder2 { continue }
+
+        // The frequency behind reaches the one ahead after some passes
+        let ((behind, behind_index), ahead_index) = if drift > 0 {
+            ((frequency1, index1), index2)
+        } else {
+            ((frequency2, index2), index1)
+        };
+
+        let passes = ((prefix[ahead_index] - behind) / drift) as usize;
+        let time = passes * n + behind_index;
 
-            visited_neg[opposite] = true;
+        if first.is_none_or(|(earliest, _)| time < earliest) {
+            first = Some((time, ahead_index));
         }
     }
 
-    panic!("{}", "Should not get here");
+    first
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{solve_part1, solve_part2};
+    use super::{input_generator, solve_part1, solve_part2, first_repeat, FrequencyReport, Repeat};
+    use examples;
     #[test]
     fn examples() {
-        assert_eq!(solve_part1(&[1,1,1]), 3);
-        assert_eq!(solve_part1(&[1,1,-2]), 0);
-        assert_eq!(solve_part1(&[-1, -2, -3]), -6);
-        assert_eq!(solve_part2(&[3, 3, 4, -2, -4]), 10);
-        assert_eq!(solve_part2(&[7, 7, -2, -7, -4]), 14);
+        let example = |i: usize| input_generator(examples::examples(1)[i].input);
+        assert_eq!(solve_part1(&example(0)), 3);
+        assert_eq!(solve_part1(&example(1)), 0);
+        assert_eq!(solve_part1(&example(2)), -6);
+        assert_eq!(solve_part2(&example(5)), 10);
+        assert_eq!(solve_part2(&example(7)), 14);
+        assert_eq!(first_repeat(&example(4)), Some(0));
+        assert_eq!(first_repeat(&example(6)), Some(5));
+        assert_eq!(first_repeat(&example(3)), Some(2));
+        assert_eq!(first_repeat(&[1, 1, 1]), None);
+        assert_eq!(first_repeat(&[1000000000, -999999999]), Some(1000000000));
+        assert_eq!(first_repeat(&[]), None);
+    }
+
+    #[test]
+    fn report() {
+        let report = FrequencyReport::new(input_generator(examples::examples(1)[3].input));
+        assert_eq!(report.changes, 4);
+        assert_eq!(report.drift, 3);
+        assert_eq!((report.min_frequency, report.max_frequency), (-1, 4));
+        assert_eq!(report.first_repeat, Some(Repeat { frequency: 2, pass: 1, index: 1, matched: Some(2) }));
+
+        let report = FrequencyReport::new(examples::examples(1)[4].input.lines().map(|l| l.parse::<isize>().unwrap()));
+        assert_eq!((report.drift, report.min_frequency, report.max_frequency), (0, 0, 1));
+        assert_eq!(report.first_repeat, Some(Repeat { frequency: 0, pass: 0, index: 1, matched: None }));
+
+        let report = FrequencyReport::new(input_generator(examples::examples(1)[5].input));
+        assert_eq!(report.first_repeat, Some(Repeat { frequency: 10, pass: 1, index: 1, matched: Some(2) }));
+        assert_eq!((report.min_frequency, report.max_frequency), (0, 10));
+
+        let report = FrequencyReport::new(vec![1, -1, 5, 7]);
+        assert_eq!(report.first_repeat, Some(Repeat { frequency: 0, pass: 0, index: 1, matched: None }));
+        assert_eq!((report.min_frequency, report.max_frequency), (0, 1));
+        assert_eq!(solve_part2(&[1, -1, 5, 7]), 0);
+
+        let report = FrequencyReport::new(vec![-1, -1]);
+        assert_eq!((report.drift, report.min_frequency, report.max_frequency), (-2, -2, 0));
+        assert_eq!(report.first_repeat, None);
     }
 }
@@ -1,11 +1,17 @@
 use std::cell::RefCell;
+use simulation::Simulation;
 
+#[derive(Clone, Debug)]
 pub struct Point {
     position: (isize, isize),
     velocity: (isize, isize)
 }
 
 impl Point {
+    pub fn position(&self) -> (isize, isize) {
+        self.position
+    }
+
     fn step(&mut self) {
         let (x, y) = self.position;
         let (xv, yv) = self.velocity;
@@ -34,43 +40,90 @@ pub fn input_generator(input: &str) -> Vec<RefCell<Point>> {
         .collect()
 }
 
-fn solve(points: &[RefCell<Point>]) -> (String, usize) {
-    let mut min_width = usize::max_value();
-    let mut last_output = "".to_string();
+pub struct Sky {
+    points: Vec<Point>,
+    time: usize,
+    width: usize,
+    min_width: usize
+}
 
-    for time in 1..100000 {
-        for i in 0..points.len() {
-            points[i].borrow_mut().step()
-        }
-        let min_x = points.iter().map(|p| p.borrow().position.0).min().unwrap();
-        let min_y = points.iter().map(|p| p.borrow().position.1).min().unwrap();
-        let max_x = points.iter().map(|p| p.borrow().position.0).max().unwrap();
-        let max_y = points.iter().map(|p| p.borrow().position.1).max().unwrap();
+impl Sky {
+    pub fn new(points: &[RefCell<Point>]) -> Sky {
+        let points = points.iter().map(|p| p.borrow().clone()).collect::<Vec<Point>>();
+        Sky { points, time: 0, width: usize::max_value(), min_width: usize::max_value() }
+    }
+
+    pub fn time(&self) -> usize {
+        self.time
+    }
+
+    // Only draws the points once they are close enough to possibly form a message
+    pub fn render(&self) -> String {
+        let min_x = self.points.iter().map(|p| p.position.0).min().unwrap();
+        let min_y = self.points.iter().map(|p| p.position.1).min().unwrap();
+        let max_x = self.points.iter().map(|p| p.position.0).max().unwrap();
+        let max_y = self.points.iter().map(|p| p.position.1).max().unwrap();
 
         let width = (max_x - min_x).abs() as usize;
         let height = (max_y - min_y).abs() as usize;
-        let mut this_output = "\n".to_string();
+        let mut output_string = "\n".to_string();
 
         if width < 100 {
             let mut output = vec![vec![" "; width + 1]; height + 1];
-            for point in points {
-                let (x, y) = point.borrow().position;
+            for point in &self.points {
+                let (x, y) = point.position;
                 output[(y - min_y) as usize][(x - min_x) as usize] = "#";
             }
 
             for line in output {
-                this_output += &line.join("");
-                this_output += "\n";
+                output_string += &line.join("");
+                output_string += "\n";
             }
         }
 
+        output_string
+    }
+}
+
+impl Simulation for Sky {
+    type State = Vec<Point>;
+
+    fn step(&mut self) {
+        for point in self.points.iter_mut() {
+            point.step()
+        }
+
+        let min_x = self.points.iter().map(|p| p.position.0).min().unwrap();
+        let max_x = self.points.iter().map(|p| p.position.0).max().unwrap();
+
+        self.min_width = usize::min(self.min_width, self.width);
+        self.width = (max_x - min_x).abs() as usize;
+        self.time += 1;
+    }
+
+    // The points have started spreading out again, so the message was
+    // visible the step before
+    fn is_finished(&self) -> bool {
+        self.time > 0 && self.width >= self.min_width
+    }
 
-        if width < min_width {
-            min_width = width;
-            last_output = this_output;
-        } else {
-            return (last_output, time - 1);
+    fn state(&self) -> &Vec<Point> {
+        &self.points
+    }
+}
+
+fn solve(points: &[RefCell<Point>]) -> (String, usize) {
+    let mut sky = Sky::new(points);
+    let mut last_output = "".to_string();
+
+    for _time in 1..100000 {
+        sky.step();
+
+        if sky.is_finished() {
+            return (last_output, sky.time() - 1);
         }
+
+        last_output = sky.render();
     }
 
     panic!("Did not finish");
@@ -91,51 +144,13 @@ pub fn solve_part2(input: &[RefCell<Point>]) -> usize {
 #[cfg(test)]
 mod tests {
     use super::{solve_part1, solve_part2, input_generator};
+    use examples;
 
     #[test]
     fn examples() {
-        let raw = "position=< 9,  1> velocity=< 0,  2>
-position=< 7,  0> velocity=<-1,  0>
-position=< 3, -2> velocity=<-1,  1>
-position=< 6, 10> velocity=<-2, -1>
-position=< 2, -4> velocity=< 2,  2>
-position=<-6, 10> velocity=< 2, -2>
-position=< 1,  8> velocity=< 1, -1>
-position=< 1,  7> velocity=< 1,  0>
-position=<-3, 11> velocity=< 1, -2>
-position=< 7,  6> velocity=<-1, -1>
-position=<-2,  3> velocity=< 1,  0>
-position=<-4,  3> velocity=< 2,  0>
-position=<10, -3> velocity=<-1,  1>
-position=< 5, 11> velocity=< 1, -2>
-position=< 4,  7> velocity=< 0, -1>
-position=< 8, -2> velocity=< 0,  1>
-position=<15,  0> velocity=<-2,  0>
-position=< 1,  6> velocity=< 1,  0>
-position=< 8,  9> velocity=< 0, -1>
-position=< 3,  3> velocity=<-1,  1>
-position=< 0,  5> velocity=< 0, -1>
-position=<-2,  2> velocity=< 2,  0>
-position=< 5, -2> velocity=< 1,  2>
-position=< 1,  4> velocity=< 2,  1>
-position=<-2,  7> velocity=< 2, -2>
-position=< 3,  6> velocity=<-1, -1>
-position=< 5,  0> velocity=< 1,  0>
-position=<-6,  0> velocity=< 2,  0>
-position=< 5,  9> velocity=< 1, -2>
-position=<14,  7> velocity=<-2,  0>
-position=<-3,  6> velocity=< 2, -1>";
-
-        assert_eq!(solve_part1(&input_generator(raw)),
-"\n".to_owned() +
-"#   #  ###\n" +
-"#   #   # \n" +
-"#   #   # \n" +
-"#####   # \n" +
-"#   #   # \n" +
-"#   #   # \n" +
-"#   #   # \n" +
-"#   #  ###\n");
+        let raw = examples::examples(10)[0].input;
+
+        assert_eq!(solve_part1(&input_generator(raw)), examples::examples(10)[0].part1.unwrap());
         assert_eq!(solve_part2(&input_generator(raw)), 3);
     }
 }
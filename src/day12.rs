@@ -1,4 +1,6 @@
 use std::boxed::Box;
+use simulation::Simulation;
+use numeric::{extrapolate, Overflow, Sum};
 
 pub type Pattern = (u8, bool);
 pub struct Input {
@@ -43,45 +45,78 @@ pub fn input_generator(input: &str) -> Box<Input> {
     Box::new(Input { initial, patterns })
 }
 
-fn run(input: &Input, generations: usize) -> isize {
-    let num_pots = 100;
-    let mut state = input.initial.clone();
-    let patterns = &input.patterns;
+const NUM_POTS: usize = 100;
 
-    let mut n_minus_2;
-    let mut n_minus_1 = 0;
-    let mut n = 0;
+fn indices(index: isize, pad: usize) -> (usize, usize) {
+    let extra = pad * 128;
+    let adjusted = extra as isize + index;
+    let position = (adjusted / 128) as usize;
 
-    let indices = |index: isize, pad: usize| -> (usize, usize) {
-        let extra = pad * 128;
-        let adjusted = extra as isize + index;
-        let position = (adjusted / 128) as usize;
+    let bitoffset = 127 - ((128 + index) % 128) as usize;
+    (position, bitoffset)
+}
+
+fn val(state: &Vec<u128>, index: isize, pad: usize) -> u128 {
+    let (pos, off) = indices(index, pad);
+    (state[pos] & (1u128 << off)) >> off
+}
+
+fn set(state: &mut Vec<u128>, index: isize, pad: usize, val: bool) {
+    let (pos, off) = indices(index, pad);
+
+    if val {
+        state[pos] |= (1 as u128) << off
+    } else {
+        state[pos] &= !((1 as u128) << off)
+    }
+}
 
-        let bitoffset = 127 - ((128 + index) % 128) as usize;
-        (position, bitoffset)
-    };
+pub struct Pots {
+    state: Vec<u128>,
+    patterns: Vec<bool>,
+    generation: usize,
+    // Sums of the current and two previous generations, the previous ones 0
+    // until there are that many generations
+    sums: (isize, isize, isize)
+}
 
-    let val = |ref state: &Vec<u128>, index: isize, pad: usize| -> u128 {
-        let (pos, off) = indices(index, pad);
-        (state[pos] & (1u128 << off)) >> off
-    };
+impl Pots {
+    pub fn new(input: &Input) -> Pots {
+        let sum = (0..128).filter(|&i| val(&input.initial, i, 0) == 1).sum();
+        Pots { state: input.initial.clone(), patterns: input.patterns.clone(), generation: 0, sums: (0, 0, sum) }
+    }
 
-    let set = |ref mut state: &mut Vec<u128>, index: isize, pad: usize, val: bool| {
-        let (pos, off) = indices(index, pad);
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
 
-        if val {
-            state[pos] |= (1 as u128) << off
+    pub fn sum(&self) -> isize {
+        self.sums.2
+    }
+
+    // It eventually reaches a steady state where sum grows by the same amount per generation
+    pub fn steady_growth(&self) -> Option<isize> {
+        let (n_minus_2, n_minus_1, n) = self.sums;
+
+        if self.generation >= 2 && n - n_minus_1 == n_minus_1 - n_minus_2 {
+            Some(n - n_minus_1)
         } else {
-            state[pos] &= !((1 as u128) << off)
+            None
         }
-    };
+    }
+}
+
+impl Simulation for Pots {
+    type State = isize;
 
-    for gen in 1..=generations {
-        n_minus_2 = n_minus_1;
-        n_minus_1 = n;
+    fn step(&mut self) {
+        self.generation += 1;
+        let gen = self.generation;
+        let state = &mut self.state;
+        let patterns = &self.patterns;
 
         let left: isize = 0 - gen as isize - 1;
-        let right: isize = num_pots as isize + gen as isize + 2;
+        let right: isize = NUM_POTS as isize + gen as isize + 2;
 
         let pad = 1 + (gen / 128);
 
@@ -108,58 +143,63 @@ fn run(input: &Input, generations: usize) -> isize {
 
         state.swap_with_slice(&mut next_state);
 
-        n = (left..right).filter(|&i| val(&state, i, pad as usize) == 1).sum();
+        let n = (left..right).filter(|&i| val(&state, i, pad as usize) == 1).sum();
+        let (_, n_minus_2, n_minus_1) = self.sums;
+        self.sums = (n_minus_2, n_minus_1, n);
+    }
 
-        // It eventually reaches a steady state where sum grows by 23 per generation
-        if n - n_minus_1 == n_minus_1 - n_minus_2 {
-            let remaining = generations as isize - gen as isize;
-            let difference = n - n_minus_1;
-            let additional = remaining * difference;
-            return n + additional;
-        }
+    fn is_finished(&self) -> bool {
+        self.steady_growth().is_some()
     }
 
-    let left: isize = 0 - generations as isize - 1;
-    let right: isize = num_pots as isize + generations as isize + 2;
-    let pad = 1 + (generations / 128);
+    fn state(&self) -> &isize {
+        &self.sums.2
+    }
+}
+
+fn run(input: &Input, generations: usize) -> Result<Sum, Overflow> {
+    let mut pots = Pots::new(input);
 
-    (left..right).filter(|&i| val(&state, i, pad as usize) == 1).sum()
+    while pots.generation() < generations {
+        pots.step();
+
+        if let Some(difference) = pots.steady_growth() {
+            let remaining = generations - pots.generation();
+            return extrapolate(pots.sum(), remaining, difference);
+        }
+    }
+
+    Ok(Sum::from(pots.sum()))
 }
 
 #[aoc(day12, part1)]
-pub fn solve_part1(input: &Input) -> isize {
-    run(input, 20)
+pub fn solve_part1(input: &Input) -> Sum {
+    run(input, 20).unwrap_or_else(|e| panic!("{}", e))
 }
 
 #[aoc(day12, part2)]
-pub fn solve_part2(input: &Input) -> isize {
-    run(input, 50000000000)
+pub fn solve_part2(input: &Input) -> Sum {
+    run(input, 50000000000).unwrap_or_else(|e| panic!("{}", e))
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{input_generator, solve_part1};
+    use super::{input_generator, solve_part1, run, Pots};
+    use examples;
+    use numeric::Sum;
+    use simulation::Simulation;
 
     #[test]
     fn examples() {
-        let raw = "initial state: #..#.#..##......###...###
-
-...## => #
-..#.. => #
-.#... => #
-.#.#. => #
-.#.## => #
-.##.. => #
-.#### => #
-#.#.# => #
-#.### => #
-##.#. => #
-##.## => #
-###.. => #
-###.# => #
-####. => #";
-
-        assert_eq!(solve_part1(&input_generator(raw)), 325);
+        let raw = examples::examples(12)[0].input;
+
+        assert_eq!(solve_part1(&input_generator(raw)), Sum::from(325isize));
+        assert_eq!(run(&input_generator(raw), 0), Ok(Sum::from(145isize)));
+
+        let mut pots = Pots::new(&input_generator(raw));
+        assert_eq!(*pots.state(), 145);
+        pots.step();
+        assert_eq!((pots.sum(), pots.steady_growth()), (91, None));
     }
 }
 
@@ -1,3 +1,5 @@
+use simulation::Simulation;
+
 #[derive(Debug, Clone)]
 pub enum Piece { Intersection, Horizontal, Vertical, CurveRight, CurveLeft, Empty }
 #[derive(Debug, Clone)]
@@ -121,25 +123,49 @@ pub fn solve_part2(input: &(Board, Elves)) -> String {
 }
 
 pub fn solve(input: &(Board, Elves)) -> ((usize, usize), (usize, usize)) {
-    let board = &input.0;
-    let mut elves = input.1.clone();
-    let num_elves = elves.len();
-    let width = board[0].len();
-    let height = board.len();
-
-    let mut occupied_by = vec![vec![None; width]; height];
+    let mut tracks = Tracks::new(input);
+    tracks.run();
 
-    for elf in &elves {
-        let (y, x) = elf.position;
-        occupied_by[y][x] = Some(elf.id);
+    match tracks.elves.first() {
+        Some(elf) => (tracks.first_crash.unwrap(), elf.position),
+        // Needed because some examples never end up with a lone elf
+        None => (tracks.first_crash.unwrap(), (0, 0))
     }
+}
+
+#[derive(Debug, Clone)]
+pub struct Tracks {
+    board: Board,
+    elves: Elves,
+    occupied_by: Vec<Vec<Option<usize>>>,
+    first_crash: Option<(usize, usize)>,
+    ticks: usize
+}
 
-    let mut first = None;
+impl Tracks {
+    pub fn new(input: &(Board, Elves)) -> Tracks {
+        let board = input.0.clone();
+        let elves = input.1.clone();
+        let width = board[0].len();
+        let height = board.len();
 
-    loop {
-        let mut crashes = vec![false; num_elves];
+        let mut occupied_by = vec![vec![None; width]; height];
 
-        for ref mut elf in &mut elves {
+        for elf in &elves {
+            let (y, x) = elf.position;
+            occupied_by[y][x] = Some(elf.id);
+        }
+
+        Tracks { board, elves, occupied_by, first_crash: None, ticks: 0 }
+    }
+
+    pub fn tick(&mut self) {
+        let board = &self.board;
+        let occupied_by = &mut self.occupied_by;
+        let first = &mut self.first_crash;
+        let mut crashes = vec![false; self.elves.iter().map(|e| e.id + 1).max().unwrap_or(0)];
+
+        for ref mut elf in &mut self.elves {
             if crashes[elf.id] { continue }
             let (y, x) = elf.position;
 
@@ -174,49 +200,91 @@ pub fn solve(input: &(Board, Elves)) -> ((usize, usize), (usize, usize)) {
                     crashes[elf.id] = true;
                     occupied_by[y][x] = None;
                     occupied_by[ny][nx] = None;
-                    if first.is_none() { first = Some((ny, nx)) }
+                    if first.is_none() { *first = Some((ny, nx)) }
                 }
             }
         }
 
-        elves.sort_by_key(|e| e.position);
-        elves = elves.into_iter().filter(|ref e| crashes[e.id] == false).collect::<Vec<Elf>>();
+        self.elves.sort_by_key(|e| e.position);
+        self.elves = self.elves.drain(..).filter(|ref e| crashes[e.id] == false).collect::<Vec<Elf>>();
+        self.ticks += 1;
+    }
+
+    pub fn ticks(&self) -> usize {
+        self.ticks
+    }
+
+    pub fn first_crash(&self) -> Option<(usize, usize)> {
+        self.first_crash
+    }
+
+    pub fn elves(&self) -> &Elves {
+        &self.elves
+    }
 
-        if elves.len() == 1 {
-            let (y, x) = elves[0].position;
-            return (first.unwrap(), (y, x));
-        } else if elves.len() == 0 {
-            // Needed because some examples never end up with a lone elf
-            return (first.unwrap(), (0, 0));
+    pub fn piece_at(&self, y: usize, x: usize) -> Option<&Piece> {
+        self.board.get(y).and_then(|row| row.get(x))
+    }
+
+    pub fn elf_at(&self, y: usize, x: usize) -> Option<&Elf> {
+        self.elves.iter().find(|e| e.position == (y, x))
+    }
+
+    pub fn render(&self) -> String {
+        let mut rows: Vec<Vec<char>> = self.board.iter().map(|row| row.iter().map(|piece| match piece {
+            Piece::Intersection => '+',
+            Piece::Horizontal => '-',
+            Piece::Vertical => '|',
+            Piece::CurveRight => '/',
+            Piece::CurveLeft => '\\',
+            Piece::Empty => ' '
+        }).collect()).collect();
+
+        for elf in &self.elves {
+            let (y, x) = elf.position;
+            rows[y][x] = match elf.direction {
+                Direction::Up => '^',
+                Direction::Down => 'v',
+                Direction::Left => '<',
+                Direction::Right => '>'
+            };
         }
+
+        rows.iter().map(|row| row.iter().collect::<String>()).collect::<Vec<String>>().join("\n")
+    }
+}
+
+impl Simulation for Tracks {
+    type State = Elves;
+
+    fn step(&mut self) {
+        self.tick()
+    }
+
+    // Runs until at most one elf is left
+    fn is_finished(&self) -> bool {
+        self.elves.len() <= 1
+    }
+
+    fn state(&self) -> &Elves {
+        &self.elves
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{input_generator, solve_part1, solve_part2};
+    use super::{input_generator, solve_part1, solve_part2, Tracks};
+    use examples;
     #[test]
     fn examples() {
-        let raw = "".to_owned() +
-"/->-\\        \n" +
-"|   |  /----\\\n" +
-"| /-+--+-\\  |\n" +
-"| | |  | v  |\n" +
-"\\-+-/  \\-+--/\n" +
-"  \\------/   \n";
-
-        assert_eq!(solve_part1(&input_generator(&raw)), "7,3");
-
-        let raw2 = "".to_owned() +
-"/>-<\\  \n" +
-"|   |  \n" +
-"| /<+-\\\n" +
-"| | | v\n" +
-"\\>+</ |\n" +
-"  |   ^\n" +
-"  \\<->/";
-
-        assert_eq!(solve_part2(&input_generator(&raw2)), "6,4");
+        let raw = examples::examples(13)[0].input;
+
+        assert_eq!(solve_part1(&input_generator(raw)), "7,3");
+        assert_eq!(Tracks::new(&input_generator(raw)).render() + "\n", raw);
+
+        let raw2 = examples::examples(13)[1].input;
+
+        assert_eq!(solve_part2(&input_generator(raw2)), "6,4");
     }
 }
 
@@ -3,14 +3,20 @@ pub fn input_generator(input: &str) -> usize {
     input.parse::<usize>().unwrap()
 }
 
+use numeric::{checked, Overflow};
+
 #[aoc(day14, part1)]
 pub fn solve_part1(input: &usize) -> String {
-    let after: usize = *input;
+    next_ten(*input).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn next_ten(after: usize) -> Result<String, Overflow> {
+    let rounds = checked(after.checked_add(10), "the number of recipes")?;
 
     let mut scoreboard: Vec<usize> = vec![3, 7];
     let mut positions = (0, 1);
 
-    for _i in 0..(after + 10) {
+    for _i in 0..rounds {
         let (current1, current2) = (scoreboard[positions.0], scoreboard[positions.1]);
         let current_score = current1 + current2;
 
@@ -26,26 +32,36 @@ pub fn solve_part1(input: &usize) -> String {
 
     }
 
-    let next_ten = &scoreboard[after..after + 10];
-    let concat = next_ten
-        .iter()
-        .enumerate()
-        .map(|(i, &n)| n as isize * (10 as isize).pow(9 - i as u32))
-        .fold(0, |acc, n| acc + n);
+    let next_ten = &scoreboard[after..rounds];
+    let mut concat: u64 = 0;
 
-    format!("{:010}", concat)
+    for (i, &n) in next_ten.iter().enumerate() {
+        let power = checked(10u64.checked_pow(9 - i as u32), "the scores")?;
+        let value = checked((n as u64).checked_mul(power), "the scores")?;
+        concat = checked(concat.checked_add(value), "the scores")?;
+    }
+
+    Ok(format!("{:010}", concat))
 }
 
 #[aoc(day14, part2)]
 pub fn solve_part2(input: &usize) -> usize {
-    let after: usize = *input;
+    recipes_before(*input).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn recipes_before(after: usize) -> Result<usize, Overflow> {
     let after_len = format!("{}", after).len();
 
     let mut scoreboard: Vec<usize> = vec![3, 7];
     let mut positions = (0, 1);
 
-    let mut sum = 37;
-    let modulo = (10 as isize).pow(after_len as u32);
+    let mut sum: isize = 37;
+    let modulo = checked(10isize.checked_pow(after_len as u32), "the score window")?;
+    let append = |sum: isize, score: usize| -> Result<isize, Overflow> {
+        let shifted = checked(sum.checked_mul(10), "the score window")?;
+        let appended = checked(shifted.checked_add(score as isize), "the score window")?;
+        Ok(appended % modulo)
+    };
 
     loop {
         let (current1, current2) = (scoreboard[positions.0], scoreboard[positions.1]);
@@ -53,15 +69,15 @@ pub fn solve_part2(input: &usize) -> usize {
 
         if current_score >= 10 {
             scoreboard.push(1);
-            sum = ((sum * 10) + 1) % modulo;
+            sum = append(sum, 1)?;
 
             if sum == after as isize {
-                return scoreboard.len() - after_len
+                return Ok(scoreboard.len() - after_len)
             }
         }
         let score = current_score % 10;
         scoreboard.push(score);
-        sum = ((sum * 10) + score as isize) % modulo;
+        sum = append(sum, score)?;
 
         positions = (
             (positions.0 + 1 + current1) % scoreboard.len(),
@@ -69,23 +85,26 @@ pub fn solve_part2(input: &usize) -> usize {
         );
 
         if sum == after as isize {
-            return scoreboard.len() - after_len
+            return Ok(scoreboard.len() - after_len)
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{solve_part1, solve_part2};
+    use super::{input_generator, solve_part1, solve_part2, recipes_before};
+    use examples;
 
     #[test]
     fn examples() {
-        assert_eq!(solve_part1(&5), "0124515891");
-        assert_eq!(solve_part1(&18), "9251071085");
-        assert_eq!(solve_part1(&2018), "5941429882");
-        assert_eq!(solve_part2(&51589), 9);
-        assert_eq!(solve_part2(&92510), 18);
-        assert_eq!(solve_part2(&59414), 2018);
+        let example = |i: usize| input_generator(examples::examples(14)[i].input);
+        assert_eq!(solve_part1(&example(1)), "0124515891");
+        assert_eq!(solve_part1(&example(2)), "9251071085");
+        assert_eq!(solve_part1(&example(3)), "5941429882");
+        assert_eq!(solve_part2(&example(4)), 9);
+        assert_eq!(solve_part2(&example(5)), 18);
+        assert_eq!(solve_part2(&example(6)), 2018);
+        assert!(recipes_before(usize::max_value()).is_err());
     }
 }
 
@@ -1,5 +1,6 @@
 use std::collections::BTreeSet;
 use std::collections::VecDeque;
+use simulation::Simulation;
 
 #[derive(Debug, Clone)]
 pub struct Unit {
@@ -44,31 +45,98 @@ pub fn solve_part2(input: &Vec<Vec<Square>>) -> usize {
 
 // (elfs_win, score)
 pub fn result(input: &Vec<Vec<Square>>, elf_power: isize) -> (bool, usize) {
-    let mut unit_positions: BTreeSet<(usize, usize)> = BTreeSet::new();
-    let mut board: Vec<Vec<Square>> = input.clone().to_vec();
-    let (height, width) = (board.len(), board[0].len());
-
-    let mut elfs_left = 0;
-    let mut goblins_left = 0;
-
-    for row in 0..height {
-        for column in 0..width {
-            match &mut board[row][column] {
-                Square::Occupied(ref mut by) => {
-                    unit_positions.insert((row, column));
-                    if by.friendly { elfs_left += 1 } else { goblins_left += 1};
-                },
-                _ => ()
-            };
+    let mut battle = Battle::new(input, elf_power);
+    battle.run();
+    battle.outcome().unwrap()
+}
+
+#[derive(Debug, Clone)]
+pub struct Battle {
+    board: Vec<Vec<Square>>,
+    unit_positions: BTreeSet<(usize, usize)>,
+    elf_power: isize,
+    elfs_left: usize,
+    goblins_left: usize,
+    original_elfs: usize,
+    full_round: bool,
+    rounds: usize
+}
+
+impl Battle {
+    pub fn new(input: &Vec<Vec<Square>>, elf_power: isize) -> Battle {
+        let mut unit_positions: BTreeSet<(usize, usize)> = BTreeSet::new();
+        let board: Vec<Vec<Square>> = input.clone().to_vec();
+        let (height, width) = (board.len(), board[0].len());
+
+        let mut elfs_left = 0;
+        let mut goblins_left = 0;
+
+        for row in 0..height {
+            for column in 0..width {
+                match &board[row][column] {
+                    Square::Occupied(by) => {
+                        unit_positions.insert((row, column));
+                        if by.friendly { elfs_left += 1 } else { goblins_left += 1};
+                    },
+                    _ => ()
+                };
+            }
+        }
+
+        Battle {
+            board, unit_positions, elf_power, elfs_left, goblins_left,
+            original_elfs: elfs_left, full_round: true, rounds: 0
+        }
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.elfs_left == 0 || self.goblins_left == 0
+    }
+
+    // Rounds started so far, including a final partial one
+    pub fn rounds(&self) -> usize {
+        self.rounds
+    }
+
+    pub fn square_at(&self, y: usize, x: usize) -> Option<&Square> {
+        self.board.get(y).and_then(|row| row.get(x))
+    }
+
+    // (elfs_win, score), once one side has been wiped out
+    pub fn outcome(&self) -> Option<(bool, usize)> {
+        if !self.is_over() { return None }
+
+        let mut outcome = 0usize;
+
+        for row in &self.board {
+            for column in row {
+                if let Square::Occupied(unit) = column {
+                    outcome += unit.hit_points as usize;
+                }
+            }
         }
+
+        let completed = if self.full_round { self.rounds } else { self.rounds - 1 };
+        Some((self.elfs_left == self.original_elfs, outcome * completed))
     }
-    let original_elfs = elfs_left;
 
-    let adjacent: Vec<(isize, isize)> = vec![(-1, 0), (0, -1), (0, 1), (1, 0)];
+    pub fn render(&self) -> String {
+        self.board.iter().map(|row| row.iter().map(|square| match square {
+            Square::Wall => '#',
+            Square::Space => '.',
+            Square::Occupied(unit) => if unit.friendly { 'E' } else { 'G' }
+        }).collect::<String>()).collect::<Vec<String>>().join("\n")
+    }
 
-    let mut full_round = true;
+    pub fn round(&mut self) {
+        if self.is_over() { return }
+        self.rounds += 1;
 
-    for round in 1.. {
+        let board = &mut self.board;
+        let (height, width) = (board.len(), board[0].len());
+        let elf_power = self.elf_power;
+        let adjacent: Vec<(isize, isize)> = vec![(-1, 0), (0, -1), (0, 1), (1, 0)];
+        let unit_positions = std::mem::replace(&mut self.unit_positions, BTreeSet::new());
         let mut new_positions: BTreeSet<(usize, usize)> = BTreeSet::new();
 
         for (mut row, mut column) in unit_positions {
@@ -77,8 +145,8 @@ pub fn result(input: &Vec<Vec<Square>>, elf_power: isize) -> (bool, usize) {
                 Square::Occupied(by) => by.friendly
             };
 
-            if goblins_left == 0 || elfs_left == 0 {
-                full_round = false;
+            if self.goblins_left == 0 || self.elfs_left == 0 {
+                self.full_round = false;
                 break;
             }
 
@@ -170,70 +238,55 @@ pub fn result(input: &Vec<Vec<Square>>, elf_power: isize) -> (bool, usize) {
                     board[y][x] = Square::Space;
                     new_positions.remove(&(y, x));
 
-                    if friendly { goblins_left -=1 } else { elfs_left -= 1 };
+                    if friendly { self.goblins_left -=1 } else { self.elfs_left -= 1 };
                 }
             }
         }
 
-        unit_positions = new_positions;
-
-        if elfs_left == 0 || goblins_left == 0 {
-            let mut outcome = 0usize;
+        self.unit_positions = new_positions;
+    }
+}
 
-            for row in &board {
-                for column in row {
-                    if let Square::Occupied(unit) = column {
-                        outcome += unit.hit_points as usize;
-                    }
-                }
-            }
+impl Simulation for Battle {
+    type State = Vec<Vec<Square>>;
 
-            if full_round { return (elfs_left == original_elfs, outcome * round) };
-            return (elfs_left == original_elfs, outcome * (round - 1));
-        }
+    fn step(&mut self) {
+        self.round()
+    }
 
+    fn is_finished(&self) -> bool {
+        self.is_over()
     }
 
-    panic!("Did not finish");
+    fn state(&self) -> &Vec<Vec<Square>> {
+        &self.board
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{solve_part1, solve_part2, input_generator};
+    use super::{solve_part1, solve_part2, input_generator, Battle};
+    use examples;
 
     #[test]
     fn examples() {
-        let raw = "#######
-#.G...#
-#...EG#
-#.#.#G#
-#..G#E#
-#.....#
-#######";
+        let raw = examples::examples(15)[0].input;
         let input = input_generator(raw);
         assert_eq!(solve_part1(&input), 27730);
         assert_eq!(solve_part2(&input), 4988);
 
-        let raw2 = "#######
-#G..#E#
-#E#E.E#
-#G.##.#
-#...#E#
-#...E.#
-#######";
+        let mut battle = Battle::new(&input, 3);
+        assert_eq!(battle.render(), raw);
+        while !battle.is_over() { battle.round() }
+        assert_eq!(battle.rounds(), 47);
+        assert_eq!(battle.outcome(), Some((false, 27730)));
+
+        let raw2 = examples::examples(15)[1].input;
 
         let input2 = input_generator(raw2);
         assert_eq!(solve_part1(&input2), 36334);
 
-        let raw3 = "#########
-#G......#
-#.E.#...#
-#..##..G#
-#...##..#
-#...#...#
-#.G...G.#
-#.....G.#
-#########";
+        let raw3 = examples::examples(15)[2].input;
 
         let input3 = input_generator(raw3);
         assert_eq!(solve_part1(&input3), 18740);
@@ -221,8 +221,13 @@ OpCode::Gtir, OpCode::Gtri, OpCode::Gtrr, OpCode::Eqir, OpCode::Eqri, OpCode::Eq
 
 #[cfg(test)]
 mod tests {
+    use super::{input_generator, solve_part1};
+    use examples;
+
     #[test]
     fn examples() {
+        // The sample behaves like mulr, addi and seti
+        assert_eq!(solve_part1(&input_generator(examples::examples(16)[0].input)), 1);
     }
 }
 
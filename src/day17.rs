@@ -1,3 +1,5 @@
+use simulation::Simulation;
+
 #[derive(Clone, Debug)]
 pub enum Tile { Sand, Clay, Water, Dried }
 
@@ -115,98 +117,128 @@ fn drip(from_y: usize, x: usize, board: &mut Vec<Vec<Tile>>) -> bool {
     false
 }
 
-#[aoc(day17, part1)]
-pub fn solve_part1(input: &Vec<Vec<Tile>>) -> isize {
-    let mut board = input.clone();
+#[derive(Clone, Debug)]
+pub struct Reservoir {
+    board: Vec<Vec<Tile>>,
+    drips: usize,
+    settled: bool
+}
 
+impl Reservoir {
+    pub fn new(input: &Vec<Vec<Tile>>) -> Reservoir {
+        Reservoir { board: input.clone(), drips: 0, settled: false }
+    }
 
-    loop {
-        if !drip(0, 500, &mut board) {
-            break;
+    // Lets one drip run from the spring, returns false once nothing more fills up
+    pub fn drip(&mut self) -> bool {
+        if self.settled { return false }
+
+        self.drips += 1;
+        if !drip(0, 500, &mut self.board) {
+            self.settled = true;
         }
+
+        !self.settled
     }
 
-    let mut sum = 0;
-    board[0][500] = Tile::Sand;
-    let mut has_something = false;
+    pub fn drips(&self) -> usize {
+        self.drips
+    }
 
-    for i in 0..(board.len()) {
-        let row = &board[i];
+    pub fn is_settled(&self) -> bool {
+        self.settled
+    }
 
+    pub fn tile_at(&self, y: usize, x: usize) -> Option<&Tile> {
+        self.board.get(y).and_then(|row| row.get(x))
+    }
 
-        for tile in row {
-            match tile {
-                Tile::Clay => has_something = true,
-                _ => ()
+    pub fn height(&self) -> usize {
+        self.board.len()
+    }
+
+    pub fn render(&self) -> String {
+        self.board.iter().map(|row| row.iter().map(|tile| match tile {
+            Tile::Sand => '.',
+            Tile::Clay => '#',
+            Tile::Water => '~',
+            Tile::Dried => '|'
+        }).collect::<String>()).collect::<Vec<String>>().join("\n")
+    }
+
+    // Counts tiles from the first row with clay in it, ignoring the spring itself
+    pub fn count(&self, counted: fn(&Tile) -> bool) -> isize {
+        let mut sum = 0;
+        let mut has_something = false;
+
+        for i in 0..self.board.len() {
+            let row = &self.board[i];
+
+            for tile in row {
+                match tile {
+                    Tile::Clay => has_something = true,
+                    _ => ()
+                }
             }
-        }
 
-        if !has_something { continue }
+            if !has_something { continue }
 
-        for tile in row {
-            match tile {
-                Tile::Water | Tile::Dried => sum += 1,
-                _other => ()
+            for (x, tile) in row.iter().enumerate() {
+                if (i, x) == (0, 500) { continue }
+                if counted(tile) { sum += 1 }
             }
         }
-    }
 
-    sum
+        sum
+    }
 }
 
-#[aoc(day17, part2)]
-pub fn solve_part2(input: &Vec<Vec<Tile>>) -> isize {
-    let mut board = input.clone();
+impl Simulation for Reservoir {
+    type State = Vec<Vec<Tile>>;
 
-
-    loop {
-        if !drip(0, 500, &mut board) {
-            break;
-        }
+    fn step(&mut self) {
+        self.drip();
     }
 
-    let mut sum = 0;
-    board[0][500] = Tile::Sand;
-    let mut has_something = false;
-
-    for i in 0..board.len() {
-        let row = &board[i];
+    fn is_finished(&self) -> bool {
+        self.settled
+    }
 
+    fn state(&self) -> &Vec<Vec<Tile>> {
+        &self.board
+    }
+}
 
-        for tile in row {
-            match tile {
-                Tile::Clay => has_something = true,
-                _ => ()
-            }
-        }
+#[aoc(day17, part1)]
+pub fn solve_part1(input: &Vec<Vec<Tile>>) -> isize {
+    let mut reservoir = Reservoir::new(input);
+    reservoir.run();
 
-        if !has_something { continue }
+    reservoir.count(|tile| match tile {
+        Tile::Water | Tile::Dried => true,
+        _other => false
+    })
+}
 
-        for tile in row {
-            match tile {
-                Tile::Water => sum += 1,
-                _other => ()
-            }
-        }
-    }
+#[aoc(day17, part2)]
+pub fn solve_part2(input: &Vec<Vec<Tile>>) -> isize {
+    let mut reservoir = Reservoir::new(input);
+    reservoir.run();
 
-    sum
+    reservoir.count(|tile| match tile {
+        Tile::Water => true,
+        _other => false
+    })
 }
 
 #[cfg(test)]
 mod tests {
     use super::{solve_part1, solve_part2, input_generator};
+    use examples;
 
     #[test]
     fn examples() {
-        let raw = "x=495, y=2..7
-y=7, x=495..501
-x=501, y=3..7
-x=498, y=2..4
-x=506, y=1..2
-x=498, y=10..13
-x=504, y=10..13
-y=13, x=498..504";
+        let raw = examples::examples(17)[0].input;
 
         assert_eq!(solve_part1(&input_generator(raw)), 57);
         assert_eq!(solve_part2(&input_generator(raw)), 29);
@@ -1,6 +1,7 @@
 use std::collections::HashMap;
+use simulation::Simulation;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum Tile { Tree, Lumberyard, Open, Null }
 
 fn char_to_tile(c: char) -> Tile {
@@ -19,66 +20,64 @@ pub fn input_generator(input: &str) -> Vec<Vec<Tile>> {
     ).collect()
 }
 
-#[aoc(day18, part1)]
-pub fn solve_part1(input: &Vec<Vec<Tile>>) -> isize {
-    let mut area = input.clone();
+fn get(area: &Vec<Vec<Tile>>, y: isize, x: isize) -> Tile {
     let height = area.len();
     let width = area[0].len();
 
-    let get = |area: &Vec<Vec<Tile>>, y: isize, x: isize| -> Tile {
-        if x >= 0 && y >= 0 && y < height as isize && x < width as isize {
-            area[y as usize][x as usize].clone()
-        } else {
-            Tile::Null
-        }
-    };
+    if x >= 0 && y >= 0 && y < height as isize && x < width as isize {
+        area[y as usize][x as usize].clone()
+    } else {
+        Tile::Null
+    }
+}
 
-    for _min in 1..=10 {
-        let mut next = area.clone();
-
-        for y in 0..area.len() {
-            for x in 0..area[0].len() {
-                let adjacent = [
-                    get(&area, y as isize - 1, x as isize - 1), get(&area, y as isize - 1, x as isize),
-                    get(&area, y as isize - 1, x as isize + 1), get(&area, y as isize, x as isize - 1),
-                    get(&area, y as isize, x as isize + 1), get(&area, y as isize + 1, x as isize - 1),
-                    get(&area, y as isize + 1, x as isize), get(&area, y as isize + 1, x as isize + 1)
-                ];
-
-                let (tree, lumber) = adjacent.iter().fold((0, 0), |(tree, lumber), tile| (
-                        match tile {
-                            Tile::Tree => (tree + 1, lumber),
-                            Tile::Lumberyard => (tree, lumber + 1),
-                            _ => (tree, lumber)
-                        }
-                ));
-
-                match area[y][x] {
-                    Tile::Open => {
-                        if tree >= 3 {
-                            next[y][x] = Tile::Tree
-                        }
-                    },
-                    Tile::Tree => {
-                        if lumber >= 3 {
-                            next[y][x] = Tile::Lumberyard
-                        }
-                    },
-                    Tile::Lumberyard => {
-                        if lumber >= 1 && tree >= 1 {
-                            next[y][x] = Tile::Lumberyard
-                        } else {
-                            next[y][x] = Tile::Open
-                        }
-                    },
-                    _ => panic!("No null tiles should exist")
-                }
+fn next_minute(area: &Vec<Vec<Tile>>) -> Vec<Vec<Tile>> {
+    let mut next = area.clone();
+
+    for y in 0..area.len() {
+        for x in 0..area[0].len() {
+            let adjacent = [
+                get(&area, y as isize - 1, x as isize - 1), get(&area, y as isize - 1, x as isize),
+                get(&area, y as isize - 1, x as isize + 1), get(&area, y as isize, x as isize - 1),
+                get(&area, y as isize, x as isize + 1), get(&area, y as isize + 1, x as isize - 1),
+                get(&area, y as isize + 1, x as isize), get(&area, y as isize + 1, x as isize + 1)
+            ];
+
+            let (tree, lumber) = adjacent.iter().fold((0, 0), |(tree, lumber), tile| (
+                    match tile {
+                        Tile::Tree => (tree + 1, lumber),
+                        Tile::Lumberyard => (tree, lumber + 1),
+                        _ => (tree, lumber)
+                    }
+            ));
+
+            match area[y][x] {
+                Tile::Open => {
+                    if tree >= 3 {
+                        next[y][x] = Tile::Tree
+                    }
+                },
+                Tile::Tree => {
+                    if lumber >= 3 {
+                        next[y][x] = Tile::Lumberyard
+                    }
+                },
+                Tile::Lumberyard => {
+                    if lumber >= 1 && tree >= 1 {
+                        next[y][x] = Tile::Lumberyard
+                    } else {
+                        next[y][x] = Tile::Open
+                    }
+                },
+                _ => panic!("No null tiles should exist")
             }
         }
-
-        area = next;
     }
 
+    next
+}
+
+fn resource_value(area: &Vec<Vec<Tile>>) -> isize {
     let (tree, lumber) = area.iter().map(|row| (
         row.iter().fold((0, 0), |(tree, lumber), tile| (
             match tile {
@@ -87,89 +86,122 @@ pub fn solve_part1(input: &Vec<Vec<Tile>>) -> isize {
                 _ => (tree, lumber)
             }
         ))
-    )).fold((0, 0), |(tree, lumber), (row_t, row_l)| (tree + row_t, lumber + row_l));;
+    )).fold((0, 0), |(tree, lumber), (row_t, row_l)| (tree + row_t, lumber + row_l));
 
     tree * lumber
 }
 
-#[aoc(day18, part2)]
-pub fn solve_part2(input: &Vec<Vec<Tile>>) -> isize {
-    let mut area = input.clone();
-    let height = area.len();
-    let width = area[0].len();
+fn dump(area: &Vec<Vec<Tile>>) -> String {
+    area.iter().map(|row| (
+        row.iter().map(|c| match c {
+            Tile::Open => '.',
+            Tile::Tree => '|',
+            Tile::Lumberyard => '#',
+            Tile::Null => ' '
+        }).collect::<String>()
+    )).collect::<Vec<String>>().join("\n")
+}
+
+#[derive(Clone, Debug)]
+pub struct Landscape {
+    area: Vec<Vec<Tile>>,
+    minute: usize,
+    // The area at the last minute that was a power of two (or 0), which the
+    // area is compared against to notice it repeating without keeping every
+    // minute around
+    saved: (usize, Vec<Vec<Tile>>),
+    period: Option<usize>
+}
 
-    let get = |area: &Vec<Vec<Tile>>, y: isize, x: isize| -> Tile {
-        if x >= 0 && y >= 0 && y < height as isize && x < width as isize {
-            area[y as usize][x as usize].clone()
-        } else {
-            Tile::Null
+impl Landscape {
+    pub fn new(input: &[Vec<Tile>]) -> Landscape {
+        let area = input.to_vec();
+        Landscape { saved: (0, area.clone()), area, minute: 0, period: None }
+    }
+
+    pub fn tick(&mut self) {
+        self.area = next_minute(&self.area);
+        self.minute += 1;
+
+        if self.period.is_none() && self.area == self.saved.1 {
+            self.period = Some(self.minute - self.saved.0);
+        }
+
+        if self.minute.is_power_of_two() {
+            self.saved = (self.minute, self.area.clone());
         }
-    };
+    }
+
+    // Number of minutes after which the area repeats, once a repeat has been
+    // seen. That happens within about twice the minutes it takes the area to
+    // start cycling.
+    pub fn period(&self) -> Option<usize> {
+        self.period
+    }
+
+    pub fn minute(&self) -> usize {
+        self.minute
+    }
+
+    pub fn tile_at(&self, y: usize, x: usize) -> Option<&Tile> {
+        self.area.get(y).and_then(|row| row.get(x))
+    }
+
+    pub fn resource_value(&self) -> isize {
+        resource_value(&self.area)
+    }
+
+    pub fn render(&self) -> String {
+        dump(&self.area)
+    }
+}
 
+impl Simulation for Landscape {
+    type State = Vec<Vec<Tile>>;
+
+    fn step(&mut self) {
+        self.tick()
+    }
+
+    // The area changes forever, but only cycles once it has repeated
+    fn is_finished(&self) -> bool {
+        self.period.is_some()
+    }
+
+    fn state(&self) -> &Vec<Vec<Tile>> {
+        &self.area
+    }
+}
+
+#[aoc(day18, part1)]
+pub fn solve_part1(input: &Vec<Vec<Tile>>) -> isize {
+    let mut landscape = Landscape::new(input);
+
+    for _min in 1..=10 {
+        landscape.tick();
+    }
+
+    landscape.resource_value()
+}
+
+#[aoc(day18, part2)]
+pub fn solve_part2(input: &Vec<Vec<Tile>>) -> isize {
+    let mut landscape = Landscape::new(input);
     let mut seen_at: HashMap<String, usize> = HashMap::new();
 
     let mut stop_at = 0;
     let mut stopping = false;
 
     for minute in 1..=1000000000 {
-        let mut next = area.clone();
-
-        for y in 0..area.len() {
-            for x in 0..area[0].len() {
-                let adjacent = [
-                    get(&area, y as isize - 1, x as isize - 1), get(&area, y as isize - 1, x as isize),
-                    get(&area, y as isize - 1, x as isize + 1), get(&area, y as isize, x as isize - 1),
-                    get(&area, y as isize, x as isize + 1), get(&area, y as isize + 1, x as isize - 1),
-                    get(&area, y as isize + 1, x as isize), get(&area, y as isize + 1, x as isize + 1)
-                ];
-
-                let (tree, lumber) = adjacent.iter().fold((0, 0), |(tree, lumber), tile| (
-                        match tile {
-                            Tile::Tree => (tree + 1, lumber),
-                            Tile::Lumberyard => (tree, lumber + 1),
-                            _ => (tree, lumber)
-                        }
-                ));
-
-                match area[y][x] {
-                    Tile::Open => {
-                        if tree >= 3 {
-                            next[y][x] = Tile::Tree
-                        }
-                    },
-                    Tile::Tree => {
-                        if lumber >= 3 {
-                            next[y][x] = Tile::Lumberyard
-                        }
-                    },
-                    Tile::Lumberyard => {
-                        if lumber >= 1 && tree >= 1 {
-                            next[y][x] = Tile::Lumberyard
-                        } else {
-                            next[y][x] = Tile::Open
-                        }
-                    },
-                    _ => panic!("No null tiles should exist")
-                }
-            }
-        }
-
-        area = next;
-
-        let dump = area.clone().iter().map(|row| (
-            row.iter().map(|c| match c {
-                Tile::Open => '.',
-                Tile::Tree => '|',
-                Tile::Lumberyard => '#',
-                Tile::Null => ' '
-            }).collect::<String>()
-        )).collect::<Vec<String>>().join("\n");
+        landscape.tick();
 
         if stopping {
             if minute == stop_at { break; }
             continue;
         }
 
+        let dump = landscape.render();
+
         if seen_at.contains_key(&dump) {
             let start = seen_at.get(&dump).unwrap().clone();
             let period = minute - start;
@@ -182,37 +214,26 @@ pub fn solve_part2(input: &Vec<Vec<Tile>>) -> isize {
         seen_at.insert(dump, minute);
     }
 
-    let (tree, lumber) = area.iter().map(|row| (
-        row.iter().fold((0, 0), |(tree, lumber), tile| (
-            match tile {
-                Tile::Tree => (tree + 1, lumber),
-                Tile::Lumberyard => (tree, lumber + 1),
-                _ => (tree, lumber)
-            }
-        ))
-    )).fold((0, 0), |(tree, lumber), (row_t, row_l)| (tree + row_t, lumber + row_l));;
-
-    tree * lumber
+    landscape.resource_value()
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{solve_part1, input_generator};
+    use super::{solve_part1, input_generator, Landscape};
+    use examples;
+    use simulation::Simulation;
 
     #[test]
     fn examples() {
-        let raw = ".#.#...|#.
-.....#|##|
-.|..|...#.
-..|#.....#
-#.#|||#|#|
-...#.||...
-.|....|...
-||...#|.#|
-|.||||..|.
-...#.|..|.";
+        let raw = examples::examples(18)[0].input;
 
         assert_eq!(solve_part1(&input_generator(raw)), 1147);
+
+        // The example ends up all open
+        let mut landscape = Landscape::new(&input_generator(raw));
+        let steps = landscape.run();
+        assert_eq!(landscape.period(), Some(1));
+        assert_eq!((landscape.minute(), landscape.resource_value()), (steps, 0));
     }
 }
 
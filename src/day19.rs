@@ -78,17 +78,11 @@ pub fn solve_part2(_input: &Input) -> usize {
 #[cfg(test)]
 mod tests {
     use super::{solve_part1, input_generator};
+    use examples;
 
     #[test]
     fn examples() {
-        let raw = "#ip 0
-seti 5 0 1
-seti 6 0 2
-addi 0 1 0
-addr 1 2 3
-setr 1 0 0
-seti 8 0 4
-seti 9 0 5";
+        let raw = examples::examples(19)[0].input;
 
         assert_eq!(solve_part1(&input_generator(raw)), 7);
     }
@@ -1,5 +1,4 @@
-use std::collections::BTreeMap;
-use itertools::Itertools;
+use std::collections::{BTreeMap, HashMap};
 
 #[aoc_generator(day2)]
 pub fn input_generator(input: &str) -> Vec<String> {
@@ -34,42 +33,138 @@ pub fn solve_part1(input: &[String]) -> i32 {
 
 #[aoc(day2, part2)]
 pub fn solve_part2(input: &[String]) -> String {
-    let words =
-        input
-            .iter()
-            .cartesian_product(input)
-            .find(|(str1, str2)| differ_by_one(str1, str2));
-
-    match words {
-        Some((word1, word2)) => {
-            common_characters(word1, word2)
-        },
+    match near_duplicates(input).into_iter().next() {
+        Some((_, _, common)) => common,
         None => panic!("Expected to find correct words")
     }
 }
 
-fn differ_by_one(str1: &str, str2: &str) -> bool {
-    let differences =
-        str1
-            .chars()
-            .zip(str2.chars())
-            .filter(|(char1, char2)|
-                 char1 != char2
-            )
-            .count();
-
-    differences == 1
+// (index of the first ID, index of the second ID, common letters)
+pub type NearDuplicate = (usize, usize, String);
+
+// Two IDs differ in exactly one position when they are equal with that
+// position left out, so every ID is bucketed under each of its masked keys
+// and only IDs sharing a bucket are compared. Returns all such pairs,
+// ordered by their indices.
+pub fn near_duplicates(input: &[String]) -> Vec<NearDuplicate> {
+    let mut buckets: HashMap<(usize, String), Vec<usize>> = HashMap::new();
+
+    for (index, id) in input.iter().enumerate() {
+        let chars: Vec<char> = id.chars().collect();
+
+        for position in 0..chars.len() {
+            let masked = chars[..position].iter().chain(&chars[position + 1..]).collect::<String>();
+
+            buckets
+                .entry((position, masked))
+                .or_default()
+                .push(index);
+        }
+    }
+
+    let mut pairs = Vec::new();
+
+    for ((_, common), indices) in buckets {
+        for (i, &index1) in indices.iter().enumerate() {
+            for &index2 in &indices[i + 1..] {
+                // Identical IDs share every bucket but differ nowhere
+                if input[index1] != input[index2] {
+                    pairs.push((index1, index2, common.clone()));
+                }
+            }
+        }
+    }
+
+    pairs.sort();
+    pairs
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Similarity {
+    // IDs of equal length differing in at most k positions
+    Hamming(usize),
+    // IDs of any length at most k insertions, deletions or substitutions apart
+    Levenshtein(usize)
 }
 
-fn common_characters(str1: &str, str2: &str) -> String {
-    str1
-        .chars()
-        .zip(str2.chars())
-        .filter(|(char1, char2)|
-             char1 == char2
-        )
-        .map(|(char1, _char2)| char1)
-        .collect::<String>()
+// Compares every pair of IDs, returning the pairs that are similar enough
+// along with the characters they have in common when aligned
+pub fn similar_ids(input: &[String], similarity: Similarity) -> Vec<NearDuplicate> {
+    let ids: Vec<Vec<char>> = input.iter().map(|id| id.chars().collect()).collect();
+    let mut pairs = Vec::new();
+
+    for (index1, id1) in ids.iter().enumerate() {
+        for (index2, id2) in ids.iter().enumerate().skip(index1 + 1) {
+            let common = match similarity {
+                Similarity::Hamming(k) => hamming(id1, id2, k),
+                Similarity::Levenshtein(k) => levenshtein(id1, id2, k)
+            };
+
+            if let Some(common) = common {
+                pairs.push((index1, index2, common));
+            }
+        }
+    }
+
+    pairs
+}
+
+fn hamming(id1: &[char], id2: &[char], k: usize) -> Option<String> {
+    if id1.len() != id2.len() { return None }
+
+    let differences = id1.iter().zip(id2).filter(|(char1, char2)| char1 != char2).count();
+    if differences > k { return None }
+
+    Some(
+        id1
+            .iter()
+            .zip(id2)
+            .filter(|(char1, char2)| char1 == char2)
+            .map(|(&char1, _)| char1)
+            .collect()
+    )
+}
+
+fn levenshtein(id1: &[char], id2: &[char], k: usize) -> Option<String> {
+    let (n, m) = (id1.len(), id2.len());
+    if n.max(m) - n.min(m) > k { return None }
+
+    // distances[i][j] between the first i characters of id1 and the first j of id2
+    let mut distances = vec![vec![0; m + 1]; n + 1];
+    for (i, row) in distances.iter_mut().enumerate() { row[0] = i }
+    for (j, distance) in distances[0].iter_mut().enumerate() { *distance = j }
+
+    for i in 1..=n {
+        for j in 1..=m {
+            let substitution = distances[i - 1][j - 1] + if id1[i - 1] == id2[j - 1] { 0 } else { 1 };
+            distances[i][j] = substitution
+                .min(distances[i - 1][j] + 1)
+                .min(distances[i][j - 1] + 1);
+        }
+    }
+
+    if distances[n][m] > k { return None }
+
+    // Walk an optimal alignment back, keeping the characters matched to themselves
+    let mut common = Vec::new();
+    let (mut i, mut j) = (n, m);
+
+    while i > 0 || j > 0 {
+        if i > 0 && j > 0 && id1[i - 1] == id2[j - 1] && distances[i][j] == distances[i - 1][j - 1] {
+            common.push(id1[i - 1]);
+            i -= 1;
+            j -= 1;
+        } else if i > 0 && j > 0 && distances[i][j] == distances[i - 1][j - 1] + 1 {
+            i -= 1;
+            j -= 1;
+        } else if i > 0 && distances[i][j] == distances[i - 1][j] + 1 {
+            i -= 1;
+        } else {
+            j -= 1;
+        }
+    }
+
+    Some(common.into_iter().rev().collect())
 }
 
 fn has_with_count(counts: &BTreeMap<char, isize>, count: isize) -> bool {
@@ -95,27 +190,36 @@ fn char_counts(str: &str) -> BTreeMap<char, isize> {
 
 #[cfg(test)]
 mod tests {
-    use super::{solve_part1, input_generator, solve_part2};
+    use super::{solve_part1, input_generator, solve_part2, near_duplicates, similar_ids, Similarity};
+    use examples;
 
     #[test]
     fn examples() {
-        let raw = "abcdef
-bababc
-abbcde
-abcccd
-aabcdd
-abcdee
-ababab";
+        let raw = examples::examples(2)[0].input;
         assert_eq!(solve_part1(&input_generator(raw)), 12);
 
-        let raw2 = "abcde
-fghij
-klmno
-pqrst
-fguij
-axcye
-wvxyz";
+        let raw2 = examples::examples(2)[1].input;
         assert_eq!(solve_part2(&input_generator(raw2)), "fgij");
+
+        let ids = input_generator("abcd\nabed\nxbcd\nabcd\nabc");
+        assert_eq!(near_duplicates(&ids), vec![
+            (0, 1, "abd".to_string()),
+            (0, 2, "bcd".to_string()),
+            (1, 3, "abd".to_string()),
+            (2, 3, "bcd".to_string())
+        ]);
+
+        let ids = input_generator("abcde\nabde\naxcye\nkitten\nsitting");
+        assert_eq!(similar_ids(&ids, Similarity::Hamming(1)), vec![]);
+        assert_eq!(similar_ids(&ids, Similarity::Hamming(2)), vec![(0, 2, "ace".to_string())]);
+        assert_eq!(similar_ids(&ids, Similarity::Levenshtein(1)), vec![(0, 1, "abde".to_string())]);
+        assert_eq!(similar_ids(&ids, Similarity::Levenshtein(3)), vec![
+            (0, 1, "abde".to_string()),
+            (0, 2, "ace".to_string()),
+            (1, 2, "ae".to_string()),
+            (3, 4, "ittn".to_string())
+        ]);
+        assert_eq!(similar_ids(&input_generator(raw2), Similarity::Hamming(1)), vec![(1, 4, "fgij".to_string())]);
     }
 }
 
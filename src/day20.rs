@@ -114,25 +114,26 @@ pub fn solve_part2(input: &str) -> usize {
 #[cfg(test)]
 mod tests {
     use super::{solve_part1};
+    use examples;
 
     #[test]
     fn examples() {
-        let reg = "^WNE$";
+        let reg = examples::examples(20)[0].input;
         assert_eq!(solve_part1(reg), 3);
 
-        let reg = "^WNE(E|N)$";
+        let reg = examples::examples(20)[1].input;
         assert_eq!(solve_part1(reg), 4);
 
-        let reg0 = "^ENWWW(NEEE|SSE(EE|N))$";
+        let reg0 = examples::examples(20)[2].input;
         assert_eq!(solve_part1(reg0), 10);
 
-        let reg1 = "^ESSWWN(E|NNENN(EESS(WNSE|)SSS|WWWSSSSE(SW|NNNE)))$";
+        let reg1 = examples::examples(20)[3].input;
         assert_eq!(solve_part1(reg1), 23);
 
-        let reg2 = "^WSSEESWWWNW(S|NENNEEEENN(ESSSSW(NWSW|SSEN)|WSWWN(E|WWS(E|SS))))$";
+        let reg2 = examples::examples(20)[4].input;
         assert_eq!(solve_part1(reg2), 31);
 
-        let reg3 = "^ENNWSWW(NEWS|)SSSEEN(WNSE|)EE(SWEN|)NNN$";
+        let reg3 = examples::examples(20)[5].input;
         assert_eq!(solve_part1(reg3), 18);
     }
 }
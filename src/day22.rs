@@ -1,5 +1,6 @@
 use std::boxed::Box;
 use std::collections::BinaryHeap;
+use numeric::{checked, Overflow};
 
 #[derive(Clone)]
 pub struct Input {
@@ -20,56 +21,66 @@ pub fn input_generator(input: &str) -> Box<Input> {
     Box::new(Input { depth, target })
 }
 
+// left and up are the erosion levels of the neighbouring regions, if any
+fn erosion_level(y: usize, x: usize, left: usize, up: usize, input: &Input) -> Result<usize, Overflow> {
+    let geologic = match (y, x) {
+        (0, 0) => Some(0),
+        coords if coords == input.target => Some(0),
+        (0, _) => x.checked_mul(16807),
+        (_, 0) => y.checked_mul(48271),
+        (_, _) => left.checked_mul(up)
+    };
+
+    let geologic = checked(geologic, "a geologic index")?;
+    Ok(checked(geologic.checked_add(input.depth), "an erosion level")? % 20183)
+}
+
 #[aoc(day22, part1)]
 pub fn solve_part1(input: &Input) -> usize {
+    risk_level(input).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn risk_level(input: &Input) -> Result<usize, Overflow> {
     let (target_x, target_y) = input.target;
-    let depth: usize = input.depth;
 
     let mut cave = vec![vec![0; target_x+1]; target_y+1];
-    let mut total = 0;
+    let mut total: usize = 0;
 
     for y in 0..=target_y {
         for x in 0..=target_x {
-            let geologic = match (y, x) {
-                (0, 0) => 0,
-                coords if coords == input.target => 0,
-                (0, _) => x * 16807,
-                (_, 0) => y * 48271,
-                (_, _) => cave[y][x-1] * cave[y-1][x]
-            };
-
-            let erosion = (geologic + depth) % 20183;
+            let left = if x > 0 { cave[y][x-1] } else { 0 };
+            let up = if y > 0 { cave[y-1][x] } else { 0 };
+
+            let erosion = erosion_level(y, x, left, up, input)?;
             cave[y][x] = erosion;
-            total += erosion % 3;
+            total = checked(total.checked_add(erosion % 3), "the risk level")?;
         }
     }
 
-    total
+    Ok(total)
 }
 
 #[aoc(day22, part2)]
 pub fn solve_part2(input: &Input) -> usize {
+    fastest_route(input).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn fastest_route(input: &Input) -> Result<usize, Overflow> {
     let (target_x, target_y) = input.target;
-    let depth: usize = input.depth;
 
     let max = std::cmp::max(target_x, target_y);
-    let width = max * 4;
-    let height = max * 4;
+    let width = checked(max.checked_mul(4), "the cave size")?;
+    let height = width;
 
     let mut erosion = vec![vec![0; width]; height];
     let mut cave = vec![vec![0; width]; height];
 
     for y in 0..height {
         for x in 0..width {
-            let geologic = match (y, x) {
-                (0, 0) => 0,
-                coords if coords == input.target => 0,
-                (0, _) => x * 16807,
-                (_, 0) => y * 48271,
-                (_, _) => erosion[y][x-1] * erosion[y-1][x]
-            };
-
-            erosion[y][x] = (geologic + depth) % 20183;
+            let left = if x > 0 { erosion[y][x-1] } else { 0 };
+            let up = if y > 0 { erosion[y-1][x] } else { 0 };
+
+            erosion[y][x] = erosion_level(y, x, left, up, input)?;
             cave[y][x] = erosion[y][x] % 3;
         }
     }
@@ -114,19 +125,20 @@ pub fn solve_part2(input: &Input) -> usize {
         frontier.push((neg_time - 7, (y, x), next_tool));
     }
 
-    shortest
+    Ok(shortest)
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{solve_part1, solve_part2, Input};
+    use super::{input_generator, solve_part1, solve_part2, risk_level, Input};
+    use examples;
 
     #[test]
     fn examples() {
-        let depth = 510;
-        let target = (10, 10);
-        assert_eq!(solve_part1(&Box::new(Input { depth, target })), 114);
-        assert_eq!(solve_part2(&Box::new(Input { depth, target })), 45);
+        let input = input_generator(examples::examples(22)[0].input);
+        assert_eq!(solve_part1(&input), 114);
+        assert_eq!(solve_part2(&input), 45);
+        assert!(risk_level(&Input { depth: usize::max_value(), target: input.target }).is_err());
     }
 }
 
@@ -82,27 +82,15 @@ pub fn solve_part2(input: &[Nanobot]) -> usize {
 #[cfg(test)]
 mod tests {
     use super::{solve_part1, solve_part2, input_generator};
+    use examples;
 
     #[test]
     fn examples() {
-        let raw = "pos=<0,0,0>, r=4
-pos=<1,0,0>, r=1
-pos=<4,0,0>, r=3
-pos=<0,2,0>, r=1
-pos=<0,5,0>, r=3
-pos=<0,0,3>, r=1
-pos=<1,1,1>, r=1
-pos=<1,1,2>, r=1
-pos=<1,3,1>, r=1";
+        let raw = examples::examples(23)[0].input;
 
         assert_eq!(solve_part1(&input_generator(raw)), 7);
 
-        let raw2 = "pos=<10,12,12>, r=2
-pos=<12,14,12>, r=2
-pos=<16,12,12>, r=4
-pos=<14,14,14>, r=6
-pos=<50,50,50>, r=200
-pos=<10,10,10>, r=5";
+        let raw2 = examples::examples(23)[1].input;
         assert_eq!(solve_part2(&input_generator(raw2)), 36);
     }
 }
@@ -1,6 +1,7 @@
 use std::boxed::Box;
 use regex::Regex;
 use std::cell::RefCell;
+use simulation::Simulation;
 
 #[derive(Clone, Debug)]
 pub struct Group {
@@ -39,6 +40,14 @@ impl Group {
     fn effective_power(&self) -> usize {
         self.units * self.damage
     }
+
+    pub fn units(&self) -> usize {
+        self.units
+    }
+
+    pub fn is_immune_system(&self) -> bool {
+        self.friendly
+    }
 }
 
 #[aoc_generator(day24)]
@@ -97,20 +106,47 @@ pub fn input_generator(input: &str) -> Vec<RefCell<Group>> {
     groups
 }
 
-pub fn outcome(input: &Vec<RefCell<Group>>, boost: usize) -> (bool, usize) {
-    let mut groups = input.clone();
+pub struct Combat {
+    groups: Vec<RefCell<Group>>,
+    stalemate: bool
+}
+
+impl Combat {
+    pub fn new(input: &Vec<RefCell<Group>>, boost: usize) -> Combat {
+        let groups = input.clone();
+
+        if boost > 0 {
+            for i in 0..groups.len() {
+                let mut group = groups[i].borrow_mut();
+                if group.friendly { group.damage += boost }
+            }
+        }
+
+        Combat { groups, stalemate: false }
+    }
 
-    let mut friendly_left;
-    let mut nonfriendly_left;
+    // (immune system wins, units left), once the fight is over
+    pub fn outcome(&self) -> Option<(bool, usize)> {
+        if self.stalemate { return Some((false, usize::max_value())) }
+        if !self.is_finished() { return None }
 
-    if boost > 0 {
-        for i in 0..groups.len() {
-            let mut group = groups[i].borrow_mut();
-            if group.friendly { group.damage += boost }
+        let friendly_left = self.groups.iter().filter(|ref g| g.borrow().friendly).count();
+        let mut sum = 0;
+        for g in &self.groups {
+            let group = g.borrow();
+            sum += group.units;
         }
+
+        Some((friendly_left > 0, sum))
     }
+}
 
-    loop {
+impl Simulation for Combat {
+    type State = Vec<RefCell<Group>>;
+
+    // Target selection followed by attacks
+    fn step(&mut self) {
+        let groups = &self.groups;
         let mut targets: Vec<Option<usize>> = vec![None; groups.len()];
         let mut chosen: Vec<bool> = vec![false; groups.len()];
         let indices = (0..groups.len()).collect::<Vec<usize>>();
@@ -164,24 +200,31 @@ pub fn outcome(input: &Vec<RefCell<Group>>, boost: usize) -> (bool, usize) {
             }
         }
 
-        if some_attack == false { return (false, usize::max_value()); }
+        if some_attack == false {
+            self.stalemate = true;
+            return;
+        }
 
-        groups = groups.into_iter().filter(|ref g| g.borrow().units > 0).collect();
-        friendly_left = groups.iter().filter(|ref g| g.borrow().friendly).count();
-        nonfriendly_left = groups.iter().filter(|ref g| !g.borrow().friendly).count();
+        self.groups.retain(|ref g| g.borrow().units > 0);
+    }
 
-        if friendly_left == 0 || nonfriendly_left == 0 {
-            break
-        }
+    // One side is wiped out, or no one can do any damage anymore
+    fn is_finished(&self) -> bool {
+        let friendly_left = self.groups.iter().filter(|ref g| g.borrow().friendly).count();
+        let nonfriendly_left = self.groups.iter().filter(|ref g| !g.borrow().friendly).count();
+
+        self.stalemate || friendly_left == 0 || nonfriendly_left == 0
     }
 
-    let mut sum = 0;
-    for g in groups {
-        let group = g.borrow();
-        sum += group.units;
+    fn state(&self) -> &Vec<RefCell<Group>> {
+        &self.groups
     }
+}
 
-    (friendly_left > 0, sum)
+pub fn outcome(input: &Vec<RefCell<Group>>, boost: usize) -> (bool, usize) {
+    let mut combat = Combat::new(input, boost);
+    combat.run();
+    combat.outcome().unwrap()
 }
 
 #[aoc(day24, part1)]
@@ -222,16 +265,11 @@ pub fn solve_part2(input: &Vec<RefCell<Group>>) -> usize {
 #[cfg(test)]
 mod tests {
     use super::{solve_part1, solve_part2, input_generator, outcome};
+    use examples;
 
     #[test]
     fn examples() {
-        let raw = "Immune System:
-17 units each with 5390 hit points (weak to radiation, bludgeoning) with an attack that does 4507 fire damage at initiative 2
-989 units each with 1274 hit points (immune to fire; weak to bludgeoning, slashing) with an attack that does 25 slashing damage at initiative 3
-
-Infection:
-801 units each with 4706 hit points (weak to radiation) with an attack that does 116 bludgeoning damage at initiative 1
-4485 units each with 2961 hit points (immune to radiation; weak to fire, cold) with an attack that does 12 slashing damage at initiative 4";
+        let raw = examples::examples(24)[0].input;
 
         assert_eq!(solve_part1(&input_generator(raw)), 5216);
 
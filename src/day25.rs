@@ -36,17 +36,11 @@ pub fn solve_part1(input: &[Point]) -> usize {
 #[cfg(test)]
 mod tests {
     use super::{solve_part1, input_generator};
+    use examples;
 
     #[test]
     fn examples() {
-         let raw = "0,0,0,0
- 3,0,0,0
- 0,3,0,0
- 0,0,3,0
- 0,0,0,3
- 0,0,0,6
- 9,0,0,0
-12,0,0,0";
+         let raw = examples::examples(25)[0].input;
          assert_eq!(solve_part1(&input_generator(raw)), 2);
     }
 }
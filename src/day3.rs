@@ -1,18 +1,21 @@
-type Specification = (u32, (u32, u32), (u32, u32));
-const WIDTH: usize = 1000;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::io::{self, Write};
+
+// (id, (x, y), (width, height))
+pub type Specification = (u32, (u64, u64), (u64, u64));
 
 fn parse(line: &str) -> Specification {
     let split_fn = |c| c == '#' || c == '@' || c == ',' || c == ':' || c == 'x';
 
-    let parts: Vec<u32> =
-        line
-            .split(split_fn)
-            .map(|part| part.trim())
-            .skip(1)
-            .map(|part| part.parse::<u32>().unwrap())
-            .collect();
+    let mut parts = line
+        .split(split_fn)
+        .map(|part| part.trim())
+        .skip(1);
+
+    let id = parts.next().unwrap().parse::<u32>().unwrap();
+    let numbers: Vec<u64> = parts.map(|part| part.parse::<u64>().unwrap()).collect();
 
-    (parts[0], (parts[1], parts[2]), (parts[3], parts[4]))
+    (id, (numbers[0], numbers[1]), (numbers[2], numbers[3]))
 }
 
 #[aoc_generator(day3)]
@@ -24,65 +27,552 @@ pub fn input_generator(input: &str) -> Vec<Specification> {
 }
 
 #[aoc(day3, part1)]
-pub fn solve_part1(input: &[Specification]) -> usize {
-    let mut fabric = vec![vec![0 as u32; WIDTH]; WIDTH];
+pub fn solve_part1(input: &[Specification]) -> u64 {
+    Fabric::new(input).overlapping_area()
+}
+
+// Claim counts over the fabric, compressed to the coordinates where a claim
+// starts or ends. Cell (i, j) spans xs[i]..xs[i + 1] and ys[j]..ys[j + 1],
+// and every square in it is covered by the same number of claims, so the
+// size only depends on the number of claims and not on their coordinates.
+pub struct Fabric {
+    xs: Vec<u64>,
+    ys: Vec<u64>,
+    counts: Vec<Vec<u32>>
+}
+
+fn edges<F: Fn(&Specification) -> (u64, u64)>(input: &[Specification], extent: F) -> Vec<u64> {
+    let mut edges: Vec<u64> = input
+        .iter()
+        .flat_map(|claim| {
+            let (start, length) = extent(claim);
+            vec![start, start + length]
+        })
+        .collect();
+
+    edges.sort();
+    edges.dedup();
+    edges
+}
+
+// Index of the cell containing coordinate, if any
+fn cell(edges: &[u64], coordinate: u64) -> Option<usize> {
+    let after = edges.partition_point(|&edge| edge <= coordinate);
+    if after == 0 || after == edges.len() { None } else { Some(after - 1) }
+}
+
+impl Fabric {
+    pub fn new(input: &[Specification]) -> Fabric {
+        let xs = edges(input, |&(_, (x, _), (w, _))| (x, w));
+        let ys = edges(input, |&(_, (_, y), (_, h))| (y, h));
+        let index = |edges: &[u64], coordinate| edges.binary_search(&coordinate).unwrap();
+
+        // Each claim adds one to a rectangle of cells, marked at its corners
+        // and summed up afterwards
+        let mut differences = vec![vec![0i64; ys.len()]; xs.len()];
 
-    for (_id, (xstart, ystart), (w, h)) in input {
-        for x in *xstart..(*xstart + *w) {
-            for y in *ystart..(*ystart + *h) {
-                fabric[x as usize][y as usize] += 1;
+        for &(_, (x, y), (w, h)) in input {
+            let (x1, x2) = (index(&xs, x), index(&xs, x + w));
+            let (y1, y2) = (index(&ys, y), index(&ys, y + h));
+
+            differences[x1][y1] += 1;
+            differences[x2][y1] -= 1;
+            differences[x1][y2] -= 1;
+            differences[x2][y2] += 1;
+        }
+
+        let columns = xs.len().saturating_sub(1);
+        let rows = ys.len().saturating_sub(1);
+        let mut counts = vec![vec![0; rows]; columns];
+        let mut column_sums = vec![0i64; rows];
+
+        for (i, column) in counts.iter_mut().enumerate() {
+            let mut sum = 0;
+
+            for (j, count) in column.iter_mut().enumerate() {
+                column_sums[j] += differences[i][j];
+                sum += column_sums[j];
+                *count = sum as u32;
             }
         }
+
+        Fabric { xs, ys, counts }
+    }
+
+    // Number of claims covering the square at (x, y)
+    pub fn count_at(&self, x: u64, y: u64) -> u32 {
+        match (cell(&self.xs, x), cell(&self.ys, y)) {
+            (Some(i), Some(j)) => self.counts[i][j],
+            _ => 0
+        }
     }
 
-    let overlaps_in_column = |column: &Vec<u32>| (
-        column
-            .iter()
-            .filter(|&n| *n > 1)
-            .count()
-    );
+    // Number of squares whose claim count satisfies the predicate, only
+    // counting squares within the bounds of the claims
+    pub fn area<F: Fn(u32) -> bool>(&self, predicate: F) -> u64 {
+        let mut area = 0;
+
+        for (i, column) in self.counts.iter().enumerate() {
+            let width = self.xs[i + 1] - self.xs[i];
 
-    fabric.iter().map(overlaps_in_column).sum()
+            for (j, &count) in column.iter().enumerate() {
+                if predicate(count) {
+                    area += width * (self.ys[j + 1] - self.ys[j]);
+                }
+            }
+        }
+
+        area
+    }
+
+    pub fn overlapping_area(&self) -> u64 {
+        self.area(|count| count > 1)
+    }
 }
 
-fn overlap(a: &Specification, b: &Specification) -> bool {
+// ((x, y), (width, height))
+pub type Rectangle = ((u64, u64), (u64, u64));
+
+// (id, other id, the rectangle both of them claim)
+pub type Conflict = (u32, u32, Rectangle);
+
+pub fn intersection(a: &Specification, b: &Specification) -> Option<Rectangle> {
     let (_, (x1, y1), (w1, h1)) = *a;
-    let (_, (x2, y2), (_w2, h2)) = *b;
+    let (_, (x2, y2), (w2, h2)) = *b;
+
+    let (left, right) = (x1.max(x2), (x1 + w1).min(x2 + w2));
+    let (top, bottom) = (y1.max(y2), (y1 + h1).min(y2 + h2));
 
-    if x1 > x2 { return overlap(b, a) }
-    if x1 + w1 <= x2 { return false }
-    if y1 + h1 <= y2 { return false }
-    if y2 + h2 <= y1 { return false }
+    if left >= right || top >= bottom { return None }
 
-    true
+    Some(((left, top), (right - left, bottom - top)))
 }
 
-#[aoc(day3, part2)]
-pub fn solve_part2(input: &[Specification]) -> u32 {
-    for &piece in input {
-        let mut free = true;
+pub fn overlap(a: &Specification, b: &Specification) -> bool {
+    intersection(a, b).is_some()
+}
+
+// Sweeps over the claims from left to right, only comparing each claim with
+// the ones still open where it starts. Returns the ids of the claims that
+// overlap nothing, in input order, and every overlapping pair ordered by
+// their position in the input.
+pub fn conflicts(input: &[Specification]) -> (Vec<u32>, Vec<Conflict>) {
+    let pairs = overlapping_pairs(input);
+    let mut overlapping = vec![false; input.len()];
+
+    for &(i, j, _) in &pairs {
+        overlapping[i] = true;
+        overlapping[j] = true;
+    }
+
+    let intact = input
+        .iter()
+        .zip(&overlapping)
+        .filter(|&(_, &overlapping)| !overlapping)
+        .map(|(&(id, _, _), _)| id)
+        .collect();
+
+    let conflicts = pairs
+        .into_iter()
+        .map(|(i, j, rectangle)| (input[i].0, input[j].0, rectangle))
+        .collect();
+
+    (intact, conflicts)
+}
+
+// (index, other index, the rectangle both claim), with index < other index
+fn overlapping_pairs(input: &[Specification]) -> Vec<(usize, usize, Rectangle)> {
+    let mut order: Vec<usize> = (0..input.len()).collect();
+    order.sort_by_key(|&i| (input[i].1).0);
+
+    // (x where the claim ends, index)
+    let mut open: BTreeSet<(u64, usize)> = BTreeSet::new();
+    let mut pairs: Vec<(usize, usize, Rectangle)> = Vec::new();
+
+    for i in order {
+        let (_, (x, _), (w, _)) = input[i];
+
+        // Claims ending at or before x can't overlap anything from here on
+        open = open.split_off(&(x + 1, 0));
+
+        for &(_, j) in &open {
+            if let Some(rectangle) = intersection(&input[i], &input[j]) {
+                pairs.push((i.min(j), i.max(j), rectangle));
+            }
+        }
+
+        open.insert((x + w, i));
+    }
+
+    pairs.sort();
+    pairs
+}
+
+// Groups of claims connected by overlaps larger than this are solved
+// heuristically, as the exact search grows exponentially
+pub const EXACT_LIMIT: usize = 40;
+
+// Largest set of claims that don't overlap each other, as ids in input
+// order. Each group of claims connected by overlaps is solved on its own:
+// exactly if it has at most EXACT_LIMIT claims, otherwise greedily.
+pub fn independent_claims(input: &[Specification]) -> Vec<u32> {
+    let mut neighbours = vec![Vec::new(); input.len()];
+
+    for (i, j, _) in overlapping_pairs(input) {
+        neighbours[i].push(j);
+        neighbours[j].push(i);
+    }
+
+    let mut component = vec![usize::MAX; input.len()];
+    let mut chosen = vec![false; input.len()];
+
+    for start in 0..input.len() {
+        if component[start] != usize::MAX { continue }
+
+        let mut members = vec![start];
+        component[start] = start;
+        let mut next = 0;
+
+        while next < members.len() {
+            for &neighbour in &neighbours[members[next]] {
+                if component[neighbour] == usize::MAX {
+                    component[neighbour] = start;
+                    members.push(neighbour);
+                }
+            }
+
+            next += 1;
+        }
+
+        let picked = if members.len() <= EXACT_LIMIT {
+            exact_independent_set(&members, &neighbours)
+        } else {
+            greedy_independent_set(&members, &neighbours)
+        };
+
+        picked.into_iter().for_each(|index| chosen[index] = true);
+    }
+
+    input
+        .iter()
+        .zip(&chosen)
+        .filter(|&(_, &chosen)| chosen)
+        .map(|(&(id, _, _), _)| id)
+        .collect()
+}
+
+fn exact_independent_set(members: &[usize], neighbours: &[Vec<usize>]) -> Vec<usize> {
+    let local: HashMap<usize, usize> = members.iter().enumerate().map(|(i, &member)| (member, i)).collect();
+    let adjacent: Vec<u64> = members
+        .iter()
+        .map(|&member| neighbours[member].iter().fold(0, |mask, neighbour| mask | 1 << local[neighbour]))
+        .collect();
+
+    let all = if members.len() == 64 { u64::MAX } else { (1 << members.len()) - 1 };
+    let mut best = 0;
+    search_independent_set(&adjacent, all, 0, &mut best);
+
+    members.iter().enumerate().filter(|&(i, _)| best & 1 << i != 0).map(|(_, &member)| member).collect()
+}
+
+// Branch and bound over the claims still available. Claims overlapping at
+// most one other available claim are always safe to take, otherwise the
+// one with the most overlaps is either taken or left out.
+fn search_independent_set(adjacent: &[u64], available: u64, chosen: u64, best: &mut u64) {
+    if chosen.count_ones() + available.count_ones() <= best.count_ones() { return }
+
+    if available == 0 {
+        *best = chosen;
+        return;
+    }
+
+    let vertices = (0..adjacent.len()).filter(|&v| available & 1 << v != 0);
+    let degree = |v: usize| (adjacent[v] & available).count_ones();
+
+    if let Some(v) = vertices.clone().find(|&v| degree(v) <= 1) {
+        search_independent_set(adjacent, available & !(adjacent[v] | 1 << v), chosen | 1 << v, best);
+        return;
+    }
+
+    let v = vertices.max_by_key(|&v| degree(v)).unwrap();
+    search_independent_set(adjacent, available & !(adjacent[v] | 1 << v), chosen | 1 << v, best);
+    search_independent_set(adjacent, available & !(1 << v), chosen, best);
+}
+
+// Repeatedly takes the claim overlapping the fewest remaining ones, then
+// improves the result by swapping one chosen claim for two that only it
+// was blocking
+fn greedy_independent_set(members: &[usize], neighbours: &[Vec<usize>]) -> Vec<usize> {
+    let mut available: HashSet<usize> = members.iter().cloned().collect();
+    let mut chosen: HashSet<usize> = HashSet::new();
+
+    while let Some(&v) = available
+        .iter()
+        .min_by_key(|&&v| (neighbours[v].iter().filter(|n| available.contains(n)).count(), v))
+    {
+        chosen.insert(v);
+        available.remove(&v);
+        neighbours[v].iter().for_each(|n| { available.remove(n); });
+    }
+
+    // Number of chosen claims overlapping each claim
+    let mut blocked_by: HashMap<usize, usize> = HashMap::new();
+    for &v in &chosen {
+        for &n in &neighbours[v] { *blocked_by.entry(n).or_insert(0) += 1 }
+    }
+
+    let mut improved = true;
+
+    while improved {
+        improved = false;
+        let mut current: Vec<usize> = chosen.iter().cloned().collect();
+        current.sort();
+
+        for x in current {
+            let candidates: Vec<usize> = neighbours[x]
+                .iter()
+                .cloned()
+                .filter(|n| blocked_by.get(n) == Some(&1))
+                .collect();
+
+            let swap = candidates.iter().enumerate().find_map(|(i, &u)| {
+                candidates[i + 1..]
+                    .iter()
+                    .find(|w| !neighbours[u].contains(w))
+                    .map(|&w| (u, w))
+            });
+
+            if let Some((u, w)) = swap {
+                chosen.remove(&x);
+                neighbours[x].iter().for_each(|n| *blocked_by.get_mut(n).unwrap() -= 1);
+
+                // Also takes any claims the removal freed up besides u and w
+                let mut freed: Vec<usize> = vec![u, w];
+                freed.extend(neighbours[x].iter().cloned().filter(|n| blocked_by[n] == 0 && *n != u && *n != w));
+
+                for v in freed {
+                    if neighbours[v].iter().any(|n| chosen.contains(n)) { continue }
 
-        for &other_piece in input {
-            if piece == other_piece { continue }
+                    chosen.insert(v);
+                    neighbours[v].iter().for_each(|&n| *blocked_by.entry(n).or_insert(0) += 1);
+                }
 
-            if overlap(&piece, &other_piece) {
-                free = false;
+                improved = true;
                 break;
             }
         }
+    }
+
+    chosen.into_iter().collect()
+}
+
+// Answers questions about a fixed set of claims. Claims are listed per
+// column of the compressed fabric for point queries, and the contested
+// cells are summed up in prefix tables so the contested area of any
+// rectangle takes a few lookups.
+pub struct ClaimIndex {
+    claims: Vec<Specification>,
+    ids: HashMap<u32, usize>,
+    fabric: Fabric,
+    // Claims covering each column of the fabric
+    columns: Vec<Vec<usize>>,
+    // Contested area of the first i columns and j rows of cells
+    contested: Vec<Vec<u64>>,
+    // Contested area of the first j rows of cells within column i
+    down: Vec<Vec<u64>>,
+    // Contested area of the first i columns of cells within row j
+    across: Vec<Vec<u64>>
+}
+
+impl ClaimIndex {
+    pub fn new(input: &[Specification]) -> ClaimIndex {
+        let fabric = Fabric::new(input);
+        let columns_count = fabric.counts.len();
+        let rows_count = fabric.ys.len().saturating_sub(1);
+
+        let mut columns = vec![Vec::new(); columns_count];
+
+        for (index, &(_, (x, _), (w, _))) in input.iter().enumerate() {
+            let start = fabric.xs.partition_point(|&edge| edge < x);
+            let end = fabric.xs.partition_point(|&edge| edge < x + w);
+            columns[start..end].iter_mut().for_each(|column| column.push(index));
+        }
+
+        let mut contested = vec![vec![0; rows_count + 1]; columns_count + 1];
+        let mut down = vec![vec![0; rows_count + 1]; columns_count];
+        let mut across = vec![vec![0; rows_count]; columns_count + 1];
+
+        for i in 0..columns_count {
+            let width = fabric.xs[i + 1] - fabric.xs[i];
+
+            for j in 0..rows_count {
+                let height = fabric.ys[j + 1] - fabric.ys[j];
+                let cell = if fabric.counts[i][j] > 1 { 1 } else { 0 };
+
+                contested[i + 1][j + 1] = contested[i][j + 1] + contested[i + 1][j] - contested[i][j]
+                    + cell * width * height;
+                down[i][j + 1] = down[i][j] + cell * height;
+                across[i + 1][j] = across[i][j] + cell * width;
+            }
+        }
+
+        let ids = input.iter().enumerate().map(|(index, &(id, _, _))| (id, index)).collect();
+
+        ClaimIndex { claims: input.to_vec(), ids, fabric, columns, contested, down, across }
+    }
+
+    // Ids of the claims covering the square at (x, y), in input order
+    pub fn claims_at(&self, x: u64, y: u64) -> Vec<u32> {
+        let column = match cell(&self.fabric.xs, x) {
+            Some(column) => &self.columns[column],
+            None => return vec![]
+        };
+
+        column
+            .iter()
+            .map(|&index| self.claims[index])
+            .filter(|&(_, (_, top), (_, h))| top <= y && y < top + h)
+            .map(|(id, _, _)| id)
+            .collect()
+    }
+
+    // None if either id is unknown
+    pub fn shared_area(&self, id1: u32, id2: u32) -> Option<u64> {
+        let a = self.claims[*self.ids.get(&id1)?];
+        let b = self.claims[*self.ids.get(&id2)?];
+
+        Some(match intersection(&a, &b) {
+            Some((_, (w, h))) => w * h,
+            None => 0
+        })
+    }
+
+    // Number of squares claimed more than once within the rectangle
+    pub fn contested_area(&self, rectangle: Rectangle) -> u64 {
+        let ((x, y), (w, h)) = rectangle;
+
+        (self.contested_before(x + w, y + h) + self.contested_before(x, y))
+            - (self.contested_before(x, y + h) + self.contested_before(x + w, y))
+    }
+
+    // Contested area of the squares left of x and above y: whole cells come
+    // from the prefix tables, and the cells cut by x or y are added scaled
+    // by how much of them is included
+    fn contested_before(&self, x: u64, y: u64) -> u64 {
+        let (xs, ys) = (&self.fabric.xs, &self.fabric.ys);
+        let columns_count = self.columns.len();
+        let rows_count = ys.len().saturating_sub(1);
+
+        let after_x = xs.partition_point(|&edge| edge <= x);
+        let after_y = ys.partition_point(|&edge| edge <= y);
+        if after_x == 0 || after_y == 0 { return 0 }
+
+        let (full_columns, full_rows) = ((after_x - 1).min(columns_count), (after_y - 1).min(rows_count));
+        let partial_column = if full_columns < columns_count { Some(x - xs[full_columns]) } else { None };
+        let partial_row = if full_rows < rows_count { Some(y - ys[full_rows]) } else { None };
+
+        let mut area = self.contested[full_columns][full_rows];
+
+        if let Some(dx) = partial_column {
+            area += dx * self.down[full_columns][full_rows];
+        }
+
+        if let Some(dy) = partial_row {
+            area += dy * self.across[full_columns][full_rows];
+        }
+
+        if let (Some(dx), Some(dy)) = (partial_column, partial_row) {
+            if self.fabric.counts[full_columns][full_rows] > 1 {
+                area += dx * dy;
+            }
+        }
+
+        area
+    }
+}
+
+// Larger fabrics are refused rather than rendered one pixel per square
+const MAX_HEATMAP_PIXELS: u64 = 1 << 26;
+const LEGEND_GAP: u64 = 2;
+const LEGEND_HEIGHT: u64 = 8;
+const OUTLINE: [u8; 3] = [0, 255, 255];
+
+// Black for unclaimed squares, then from dark red through yellow to white as
+// the count approaches the highest one
+fn heat(count: u32, max: u32) -> [u8; 3] {
+    if count == 0 { return [0, 0, 0] }
+
+    let t = count as f64 / max as f64;
+    let channel = |offset: f64| (255.0 * (t * 3.0 - offset).clamp(0.0, 1.0)) as u8;
+    [channel(0.0).max(64), channel(1.0), channel(2.0)]
+}
+
+// Writes the number of claims per square as a binary PPM image, one pixel
+// per square from the origin to the furthest claim. Below it is a legend
+// running from 0 claims on the left to the highest count on the right, and
+// the highlighted claim (if any) is outlined.
+pub fn write_heatmap<W: Write>(out: &mut W, input: &[Specification], highlight: Option<u32>) -> io::Result<()> {
+    let invalid = |message: String| io::Error::new(io::ErrorKind::InvalidInput, message);
 
-        if free {
-            let (id, _, _) = piece;
-            return id;
+    let highlighted = match highlight {
+        Some(id) => match input.iter().find(|&&(other, _, _)| other == id) {
+            Some(&claim) => Some(claim),
+            None => return Err(invalid(format!("No claim with id {}", id)))
+        },
+        None => None
+    };
+
+    let fabric = Fabric::new(input);
+    let width = fabric.xs.last().cloned().unwrap_or(0).max(1);
+    let height = fabric.ys.last().cloned().unwrap_or(0).max(1);
+    let total_height = height + LEGEND_GAP + LEGEND_HEIGHT;
+
+    if width.saturating_mul(total_height) > MAX_HEATMAP_PIXELS {
+        return Err(invalid(format!("Fabric of {}x{} is too large for a heatmap", width, height)));
+    }
+
+    let max = fabric.counts.iter().flat_map(|column| column.iter().cloned()).max().unwrap_or(0).max(1);
+    let outlined = |x: u64, y: u64| match highlighted {
+        Some((_, (left, top), (w, h))) => {
+            let inside = left <= x && x < left + w && top <= y && y < top + h;
+            inside && (x == left || x + 1 == left + w || y == top || y + 1 == top + h)
+        },
+        None => false
+    };
+
+    let mut pixels = Vec::with_capacity((width * total_height * 3) as usize);
+
+    for y in 0..total_height {
+        for x in 0..width {
+            let color = if y < height {
+                if outlined(x, y) { OUTLINE } else { heat(fabric.count_at(x, y), max) }
+            } else if y < height + LEGEND_GAP {
+                [255, 255, 255]
+            } else {
+                heat((x * (max as u64 + 1) / width) as u32, max)
+            };
+
+            pixels.extend_from_slice(&color);
         }
     }
 
-    panic!("Found no non-overlapping piece");
+    write!(out, "P6\n# claims per square, legend from 0 (left) to {} (right)\n{} {}\n255\n", max, width, total_height)?;
+    out.write_all(&pixels)
+}
+
+#[aoc(day3, part2)]
+pub fn solve_part2(input: &[Specification]) -> u32 {
+    match conflicts(input).0.first() {
+        Some(&id) => id,
+        None => panic!("Found no non-overlapping piece")
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{input_generator, solve_part1, solve_part2, overlap};
+    use super::{input_generator, solve_part1, solve_part2, overlap, conflicts, independent_claims, write_heatmap,
+                ClaimIndex, Fabric, Specification};
+    use examples;
 
     #[test]
     fn test_overlap() {
@@ -99,12 +589,140 @@ mod tests {
     }
     #[test]
     fn examples() {
-        let raw = "#1 @ 1,3: 4x4
-#2 @ 3,1: 4x4
-#3 @ 5,5: 2x2";
+        let raw = examples::examples(3)[0].input;
         let input = input_generator(raw);
         assert_eq!(solve_part1(&input), 4);
         assert_eq!(solve_part2(&input), 3);
+        assert_eq!(input_generator("#4294967295 @ 1,3: 4x4"), vec![(4294967295, (1, 3), (4, 4))]);
+
+        let fabric = Fabric::new(&input);
+        assert_eq!((fabric.count_at(3, 3), fabric.count_at(1, 3), fabric.count_at(0, 0)), (2, 1, 0));
+        assert_eq!(fabric.area(|count| count == 1), 24 + 4);
+
+        assert_eq!(conflicts(&input), (vec![3], vec![(1, 2, ((3, 3), (2, 2)))]));
     }
-}
 
+    #[test]
+    fn all_conflicts() {
+        let input = input_generator("#1 @ 0,0: 10x2
+#2 @ 2,1: 2x5
+#3 @ 10,0: 1x1
+#4 @ 3,4: 4x4
+#5 @ 20,20: 1x1
+#6 @ 6,0: 1x8");
+        let (intact, overlapping) = conflicts(&input);
+        assert_eq!(intact, vec![3, 5]);
+        assert_eq!(overlapping, vec![
+            (1, 2, ((2, 1), (2, 1))),
+            (1, 6, ((6, 0), (1, 2))),
+            (2, 4, ((3, 4), (1, 2))),
+            (4, 6, ((6, 4), (1, 4)))
+        ]);
+    }
+
+    #[test]
+    fn independent() {
+        let input = input_generator(examples::examples(3)[0].input);
+        assert_eq!(independent_claims(&input), vec![1, 3]);
+
+        let input = input_generator("#1 @ 0,0: 10x2
+#2 @ 2,1: 2x5
+#3 @ 10,0: 1x1
+#4 @ 3,4: 4x4
+#5 @ 20,20: 1x1
+#6 @ 6,0: 1x8");
+        assert_eq!(independent_claims(&input), vec![1, 3, 4, 5]);
+
+        // A chain of claims each overlapping the next, all overlapped by one
+        // long claim, is too large to solve exactly
+        let mut input: Vec<Specification> = (0..60).map(|i| (i as u32 + 1, (i * 2, 0), (3, 1))).collect();
+        input.push((61, (0, 0), (200, 1)));
+        let chosen = independent_claims(&input);
+        assert_eq!(chosen.len(), 30);
+
+        for (i, &id1) in chosen.iter().enumerate() {
+            for &id2 in &chosen[i + 1..] {
+                assert!(!overlap(&input[id1 as usize - 1], &input[id2 as usize - 1]));
+            }
+        }
+    }
+
+    #[test]
+    fn heatmap() {
+        let input = input_generator(examples::examples(3)[0].input);
+        let mut image = Vec::new();
+        write_heatmap(&mut image, &input, Some(3)).unwrap();
+
+        let header = "P6\n# claims per square, legend from 0 (left) to 2 (right)\n7 17\n255\n";
+        assert!(image.starts_with(header.as_bytes()));
+
+        let pixels = &image[header.len()..];
+        assert_eq!(pixels.len(), 7 * 17 * 3);
+
+        let pixel = |x: usize, y: usize| &pixels[(y * 7 + x) * 3..(y * 7 + x + 1) * 3];
+        assert_eq!(pixel(0, 0), &[0, 0, 0]);
+        assert_eq!(pixel(3, 3), &[255, 255, 255]);
+        assert_eq!(pixel(5, 6), &[0, 255, 255]);
+        assert!(pixel(1, 3) != pixel(3, 3) && pixel(1, 3) != pixel(0, 0));
+        assert_eq!(pixel(0, 8), &[255, 255, 255]);
+        assert_eq!((pixel(0, 16), pixel(3, 16), pixel(6, 16)), (pixel(0, 0), pixel(1, 3), pixel(3, 3)));
+
+        assert!(write_heatmap(&mut Vec::new(), &input, Some(4)).is_err());
+        assert!(write_heatmap(&mut Vec::new(), &input_generator("#1 @ 0,0: 100000x100000"), None).is_err());
+    }
+
+    #[test]
+    fn queries() {
+        let input = input_generator(examples::examples(3)[0].input);
+        let index = ClaimIndex::new(&input);
+        assert_eq!(index.claims_at(3, 3), vec![1, 2]);
+        assert_eq!(index.claims_at(5, 5), vec![3]);
+        assert_eq!(index.claims_at(0, 0), vec![]);
+        assert_eq!(index.shared_area(1, 2), Some(4));
+        assert_eq!(index.shared_area(1, 3), Some(0));
+        assert_eq!(index.shared_area(1, 9), None);
+        assert_eq!(index.contested_area(((0, 0), (10, 10))), 4);
+        assert_eq!(index.contested_area(((4, 2), (5, 5))), 2);
+
+        let input = input_generator("#1 @ 0,0: 10x2
+#2 @ 2,1: 2x5
+#3 @ 10,0: 1x1
+#4 @ 3,4: 4x4
+#5 @ 20,20: 1x1
+#6 @ 6,0: 1x8");
+        let index = ClaimIndex::new(&input);
+        let fabric = Fabric::new(&input);
+
+        for x in 0..12 {
+            for y in 0..10 {
+                for w in 0..6 {
+                    for h in 0..6 {
+                        let expected = (x..x + w)
+                            .flat_map(|sx| (y..y + h).map(move |sy| (sx, sy)))
+                            .filter(|&(sx, sy)| fabric.count_at(sx, sy) > 1)
+                            .count() as u64;
+                        assert_eq!(index.contested_area(((x, y), (w, h))), expected);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn large_coordinates() {
+        let input = input_generator("#1 @ 1000000000000,5: 3x3
+#2 @ 1000000000001,6: 3x3
+#3 @ 4000000000,4000000000: 2000000000x1000000000");
+        assert_eq!(solve_part1(&input), 4);
+
+        let fabric = Fabric::new(&input);
+        assert_eq!(fabric.count_at(1000000000002, 7), 2);
+        assert_eq!(fabric.area(|count| count > 0), 14 + 2000000000000000000);
+    }
+
+    #[test]
+    #[should_panic]
+    fn id_out_of_range() {
+        input_generator("#4294967296 @ 1,3: 4x4");
+    }
+}
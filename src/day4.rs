@@ -1,15 +1,18 @@
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
 use std::str::FromStr;
 use std::cmp::Ordering;
 use regex::Regex;
 
-#[derive(Eq, PartialEq, Debug)]
-enum Observation {
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub enum Observation {
     BeginsShift(u32),
     FallsAsleep,
     WakesUp
 }
 
-#[derive(Eq, Debug)]
+#[derive(Eq, Debug, Clone)]
 pub struct LogEntry {
     date: (u32, u8, u8),
     time: (u8, u8),
@@ -45,11 +48,15 @@ impl FromStr for Observation {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let mut parts = s.split(" ");
 
-        match parts.next().unwrap() {
-            "Guard" => Ok(Observation::BeginsShift(parts.next().unwrap().replace("#", "").parse::<u32>().unwrap())),
-            "falls" => Ok(Observation::FallsAsleep),
-            "wakes" => Ok(Observation::WakesUp),
-            &_ => Err(())
+        match parts.next() {
+            Some("Guard") => parts
+                .next()
+                .and_then(|id| id.replace("#", "").parse::<u32>().ok())
+                .map(Observation::BeginsShift)
+                .ok_or(()),
+            Some("falls") => Ok(Observation::FallsAsleep),
+            Some("wakes") => Ok(Observation::WakesUp),
+            _ => Err(())
         }
     }
 }
@@ -60,20 +67,30 @@ impl FromStr for LogEntry {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
 
         let re = Regex::new(r"\[(\d{4})-(\d{2})-(\d{2}) (\d{2}):(\d{2})\] (.*)").unwrap();
-        let capture = re.captures(s).unwrap();
-        let mut caps = capture.iter().skip(1);
+        let capture = re.captures(s).ok_or(())?;
+        // The pattern only matches digits here, so these always parse
+        let number = |i: usize| capture[i].parse::<u32>().unwrap();
 
-        let date_parts: Vec<u32> = caps.by_ref().take(3).map(|c| c.unwrap().as_str().parse::<u32>().unwrap()).collect();
-        let time_parts: Vec<u8> = caps.by_ref().take(2).map(|c| c.unwrap().as_str().parse::<u8>().unwrap()).collect();
-
-        let date = (date_parts[0], date_parts[1] as u8, date_parts[2] as u8);
-        let time = (time_parts[0], time_parts[1]);
-        let observation: Observation = caps.next().unwrap().unwrap().as_str().parse::<Observation>().unwrap();
+        let date = (number(1), number(2) as u8, number(3) as u8);
+        let time = (number(4) as u8, number(5) as u8);
+        let observation: Observation = capture[6].parse::<Observation>()?;
 
         Ok(LogEntry { date: date, time: time, observation: observation })
     }
 }
 
+// (index of the source, line number counting from 1, the line)
+#[derive(Debug, Clone, PartialEq)]
+pub struct MalformedLine(pub usize, pub usize, pub String);
+
+impl fmt::Display for MalformedLine {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Malformed log entry in source {} on line {}: {:?}", self.0, self.1, self.2)
+    }
+}
+
+impl Error for MalformedLine {}
+
 #[aoc_generator(day4)]
 pub fn input_generator(input: &str) -> Vec<LogEntry> {
     let mut parsed =
@@ -86,118 +103,480 @@ pub fn input_generator(input: &str) -> Vec<LogEntry> {
     parsed
 }
 
-#[aoc(day4, part1)]
-pub fn solve_part1(input: &[LogEntry]) -> u32 {
-    let mut guard: u32 = 0;
-    let mut minutes = vec![vec![0 as u32; 60]; 4096];
-    let mut iter = input.iter();
+// (time, the differing observations in source order)
+pub type LogConflict = (Timestamp, Vec<Observation>);
 
-    while let Some(entry) = iter.next() {
-        match entry.observation {
-            Observation::BeginsShift(n) => guard = n,
-            Observation::FallsAsleep => {
-                let next_entry = iter.next().unwrap();
-                let (_, minute_end) = next_entry.time;
-                let (_, minute_start) = entry.time;
+// Combines partial logs in any order into one sorted log. Exact duplicates
+// are dropped, and when entries at the same time disagree the first one
+// (in source order) is kept and the disagreement reported. Fails on the first
+// line that isn't a log entry.
+pub fn merge_logs<'a, I: IntoIterator<Item = &'a str>>(sources: I) -> Result<(Vec<LogEntry>, Vec<LogConflict>), MalformedLine> {
+    let mut entries: Vec<LogEntry> = Vec::new();
+
+    for (source, log) in sources.into_iter().enumerate() {
+        for (number, line) in log.lines().enumerate() {
+            if line.trim().is_empty() { continue }
+
+            match line.parse::<LogEntry>() {
+                Ok(entry) => entries.push(entry),
+                Err(()) => return Err(MalformedLine(source, number + 1, line.to_string()))
+            }
+        }
+    }
+
+    // Stable, so entries at the same time stay in source order
+    entries.sort();
 
-                for i in minute_start..minute_end {
-                    minutes[guard as usize][i as usize] += 1;
+    let mut merged: Vec<LogEntry> = Vec::new();
+    let mut conflicts: Vec<LogConflict> = Vec::new();
+
+    for entry in entries {
+        let at = entry.timestamp();
+
+        match merged.last() {
+            Some(last) if last.timestamp() == at => {
+                if *last == entry { continue }
+
+                match conflicts.last_mut() {
+                    Some(&mut (time, ref mut observations)) if time == at => {
+                        if !observations.contains(&entry.observation) {
+                            observations.push(entry.observation);
+                        }
+                    },
+                    _ => conflicts.push((at, vec![last.observation.clone(), entry.observation]))
                 }
             },
-            _ => panic!("")
+            _ => merged.push(entry)
         }
     }
 
-    let num_minutes = |minutes: &Vec<u32>| -> u32 {
-        minutes.iter().sum()
-    };
+    Ok((merged, conflicts))
+}
+
+// (date of the midnight hour, guard, whether asleep during each of its
+// minutes)
+type MidnightHour = ((u32, u8, u8), u32, Vec<bool>);
+
+// Shifts beginning in the evening are on duty the midnight hour of the next
+// day
+fn midnight_hours(input: &[LogEntry]) -> Vec<MidnightHour> {
+    reconstruct(input)
+        .0
+        .into_iter()
+        .map(|shift| {
+            let (began_date, (hour, _)) = shift.began;
+            let day = day_number(began_date) + if hour >= 12 { 1 } else { 0 };
+            let midnight = day * 24 * 60;
+
+            let asleep = (midnight..midnight + 60)
+                .map(|minute| {
+                    shift.naps.iter().any(|&(start, end)| minute_number(start) <= minute && minute < minute_number(end))
+                })
+                .collect();
+
+            (date_from_day_number(day), shift.guard, asleep)
+        })
+        .collect()
+}
+
+// The log drawn like in the puzzle description, one row per shift with #
+// for each minute of the midnight hour the guard was asleep
+pub fn timeline(input: &[LogEntry]) -> String {
+    let hours = midnight_hours(input);
+    let id_width = hours.iter().map(|&(_, guard, _)| format!("#{}", guard).len()).max().unwrap_or(0).max(2);
+    let indent = " ".repeat(5 + 2 + id_width + 2);
 
-    let (guard, minutes) =
-        minutes
-            .iter()
-            .enumerate()
-            .max_by(|(_, minutes1), (_, minutes2)| num_minutes(minutes1).cmp(&num_minutes(minutes2)))
-            .unwrap();
+    let mut lines = vec![format!("Date   {:<width$}  Minute", "ID", width = id_width)];
+    lines.push(format!("{}{}", indent, (0..60).map(|minute| (b'0' + minute / 10) as char).collect::<String>()));
+    lines.push(format!("{}{}", indent, (0..60).map(|minute| (b'0' + minute % 10) as char).collect::<String>()));
 
-    let (minute, _count) = minutes
-        .iter()
-        .enumerate()
-        .max_by(|(_, count1), (_, count2)| count1.cmp(count2))
-        .unwrap();
+    for ((_, month, day), guard, asleep) in hours {
+        let minutes: String = asleep.iter().map(|&asleep| if asleep { '#' } else { '.' }).collect();
+        lines.push(format!("{:02}-{:02}  {:<width$}  {}", month, day, format!("#{}", guard), minutes, width = id_width));
+    }
+
+    lines.join("\n")
+}
+
+// Same as timeline, with 1 for asleep and 0 for awake in columns 0 to 59
+pub fn timeline_csv(input: &[LogEntry]) -> String {
+    let mut lines = vec![format!("date,guard,{}", (0..60).map(|minute| minute.to_string()).collect::<Vec<String>>().join(","))];
+
+    for ((year, month, day), guard, asleep) in midnight_hours(input) {
+        let minutes: Vec<&str> = asleep.iter().map(|&asleep| if asleep { "1" } else { "0" }).collect();
+        lines.push(format!("{}-{:02}-{:02},{},{}", year, month, day, guard, minutes.join(",")));
+    }
 
-    guard as u32 * minute as u32
+    lines.join("\n")
+}
+
+#[aoc(day4, part1)]
+pub fn solve_part1(input: &[LogEntry]) -> u64 {
+    GuardSchedule::new(input)
+        .unwrap_or_else(|e| panic!("{}", e))
+        .strategy1()
+        .expect("Expected some guard to sleep")
 }
 
 #[aoc(day4, part2)]
-pub fn solve_part2(input: &[LogEntry]) -> u32 {
-    let mut guard: u32 = 0;
-    let mut minutes = vec![vec![0 as u32; 60]; 4096];
-    let mut iter = input.iter();
+pub fn solve_part2(input: &[LogEntry]) -> u64 {
+    GuardSchedule::new(input)
+        .unwrap_or_else(|e| panic!("{}", e))
+        .strategy2()
+        .expect("Expected some guard to sleep")
+}
+
+// (date, time)
+pub type Timestamp = ((u32, u8, u8), (u8, u8));
+
+impl LogEntry {
+    pub fn timestamp(&self) -> Timestamp {
+        (self.date, self.time)
+    }
+}
+
+// Days since 1970-01-01 in the proleptic Gregorian calendar
+fn day_number((year, month, day): (u32, u8, u8)) -> i64 {
+    let (month, day) = (month as i64, day as i64);
+    let year = year as i64 - if month <= 2 { 1 } else { 0 };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+    era * 146097 + day_of_era - 719468
+}
+
+// Minutes since 1970-01-01 00:00, so naps can span hours and days
+fn minute_number((date, (hour, minute)): Timestamp) -> i64 {
+    day_number(date) * 24 * 60 + hour as i64 * 60 + minute as i64
+}
+
+// Inverse of day_number
+fn date_from_day_number(days: i64) -> (u32, u8, u8) {
+    let days = days + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days - era * 146097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    (year as u32, month as u8, day as u8)
+}
+
+fn format_timestamp(((year, month, day), (hour, minute)): Timestamp) -> String {
+    format!("{}-{:02}-{:02} {:02}:{:02}", year, month, day, hour, minute)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum LogError {
+    DoubleSleep(Timestamp),
+    OrphanWake(Timestamp),
+    // Fell asleep or woke up before any guard began a shift
+    NoGuard(Timestamp),
+    // The shift that began at this time ended with the guard still asleep
+    UnfinishedNap(Timestamp)
+}
+
+impl fmt::Display for LogError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            LogError::DoubleSleep(at) => write!(f, "Guard fell asleep at {} while already asleep", format_timestamp(at)),
+            LogError::OrphanWake(at) => write!(f, "Guard woke up at {} without falling asleep", format_timestamp(at)),
+            LogError::NoGuard(at) => write!(f, "Entry at {} comes before any guard began a shift", format_timestamp(at)),
+            LogError::UnfinishedNap(at) => write!(f, "Shift beginning at {} ends with the guard asleep", format_timestamp(at))
+        }
+    }
+}
+
+impl Error for LogError {}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Shift {
+    pub guard: u32,
+    pub began: Timestamp,
+    // (falling asleep, waking up)
+    pub naps: Vec<(Timestamp, Timestamp)>
+}
+
+// Shifts reconstructed from a sorted log, with how often each guard was
+// asleep during each minute of the hour
+pub struct GuardSchedule {
+    shifts: Vec<Shift>,
+    minutes: BTreeMap<u32, [u32; 60]>
+}
+
+// Every problem found in a sorted log, in order
+pub fn validate(input: &[LogEntry]) -> Vec<LogError> {
+    reconstruct(input).1
+}
+
+// Entries that can't be placed (see LogError) are skipped
+fn reconstruct(input: &[LogEntry]) -> (Vec<Shift>, Vec<LogError>) {
+    let mut shifts: Vec<Shift> = Vec::new();
+    let mut errors = Vec::new();
+    let mut asleep: Option<Timestamp> = None;
+
+    for entry in input {
+        let at = entry.timestamp();
 
-    while let Some(entry) = iter.next() {
         match entry.observation {
-            Observation::BeginsShift(n) => guard = n,
-            Observation::FallsAsleep => {
-                let next_entry = iter.next().unwrap();
-                let (_, minute_end) = next_entry.time;
-                let (_, minute_start) = entry.time;
+            Observation::BeginsShift(guard) => {
+                if asleep.take().is_some() {
+                    errors.push(LogError::UnfinishedNap(shifts[shifts.len() - 1].began));
+                }
 
-                for i in minute_start..minute_end {
-                    minutes[guard as usize][i as usize] += 1;
+                shifts.push(Shift { guard, began: at, naps: vec![] });
+            },
+            Observation::FallsAsleep => {
+                if shifts.is_empty() {
+                    errors.push(LogError::NoGuard(at));
+                } else if asleep.is_some() {
+                    errors.push(LogError::DoubleSleep(at));
+                } else {
+                    asleep = Some(at);
                 }
             },
-            _ => panic!("")
+            Observation::WakesUp => {
+                if shifts.is_empty() {
+                    errors.push(LogError::NoGuard(at));
+                } else {
+                    match asleep.take() {
+                        Some(start) => shifts.last_mut().unwrap().naps.push((start, at)),
+                        None => errors.push(LogError::OrphanWake(at))
+                    }
+                }
+            }
+        }
+    }
+
+    if asleep.is_some() {
+        errors.push(LogError::UnfinishedNap(shifts[shifts.len() - 1].began));
+    }
+
+    (shifts, errors)
+}
+
+impl GuardSchedule {
+    // Fails with the first problem in the log
+    pub fn new(input: &[LogEntry]) -> Result<GuardSchedule, LogError> {
+        let (shifts, errors) = reconstruct(input);
+        if let Some(error) = errors.into_iter().next() { return Err(error) }
+
+        let mut minutes: BTreeMap<u32, [u32; 60]> = BTreeMap::new();
+
+        for shift in &shifts {
+            let histogram = minutes.entry(shift.guard).or_insert([0; 60]);
+
+            for &(start, end) in &shift.naps {
+                for minute in minute_number(start)..minute_number(end) {
+                    histogram[minute.rem_euclid(60) as usize] += 1;
+                }
+            }
         }
+
+        Ok(GuardSchedule { shifts, minutes })
+    }
+
+    pub fn shifts(&self) -> &[Shift] {
+        &self.shifts
+    }
+
+    // In ascending order
+    pub fn guards(&self) -> Vec<u32> {
+        self.minutes.keys().cloned().collect()
+    }
+
+    // Number of times the guard was asleep during each minute of the hour
+    pub fn histogram(&self, guard: u32) -> Option<&[u32; 60]> {
+        self.minutes.get(&guard)
     }
 
-    let max_minute = |minutes: &Vec<u32>| {
-        let (minute, count) =
-            minutes
+    pub fn total_sleep(&self, guard: u32) -> u32 {
+        self.histogram(guard).map_or(0, |histogram| histogram.iter().sum())
+    }
+
+    // (minute, times asleep during it), the latest minute on ties
+    pub fn sleepiest_minute(&self, guard: u32) -> Option<(u8, u32)> {
+        self.histogram(guard).and_then(|histogram| {
+            histogram
                 .iter()
                 .enumerate()
-                .max_by(|(_, count1), (_, count2)| count1.cmp(count2))
-                .unwrap();
+                .max_by_key(|&(_, &count)| count)
+                .map(|(minute, &count)| (minute as u8, count))
+        })
+    }
 
-        (minute, count.clone())
-    };
+    // The guard with the most total sleep times their sleepiest minute, the
+    // highest id on ties. None if no guard ever slept.
+    pub fn strategy1(&self) -> Option<u64> {
+        let guard = self.guards().into_iter().max_by_key(|&guard| self.total_sleep(guard))?;
+        let (minute, count) = self.sleepiest_minute(guard)?;
+        if count == 0 { return None }
 
-    let (guard, (minute, _count)) =
-        minutes
-            .iter()
-            .enumerate()
-            .map(|(guard, minutes)| (guard, max_minute(minutes)))
-            .max_by(|(_, (_, c1)), (_, (_, c2))| c1.cmp(c2))
-            .unwrap();
+        Some(guard as u64 * minute as u64)
+    }
 
-    guard as u32 * minute as u32
+    // The guard most frequently asleep on the same minute times that minute.
+    // None if no guard ever slept.
+    pub fn strategy2(&self) -> Option<u64> {
+        let (guard, (minute, count)) = self
+            .guards()
+            .into_iter()
+            .filter_map(|guard| self.sleepiest_minute(guard).map(|sleepiest| (guard, sleepiest)))
+            .max_by_key(|&(_, (_, count))| count)?;
+        if count == 0 { return None }
+
+        Some(guard as u64 * minute as u64)
+    }
 }
 
-
 #[cfg(test)]
 mod tests {
-    use super::{solve_part1, solve_part2, input_generator};
+    use super::{solve_part1, solve_part2, input_generator, merge_logs, timeline, timeline_csv, validate,
+                GuardSchedule, LogError, MalformedLine, Observation};
+    use examples;
 
     #[test]
     fn examples() {
-        let raw = "[1518-11-01 00:00] Guard #10 begins shift
+        let raw = examples::examples(4)[0].input;
+        assert_eq!(solve_part1(&input_generator(raw)), 240);
+        assert_eq!(solve_part2(&input_generator(raw)), 4455);
+
+        let schedule = GuardSchedule::new(&input_generator(raw)).unwrap();
+        assert_eq!(schedule.guards(), vec![10, 99]);
+        assert_eq!((schedule.total_sleep(10), schedule.total_sleep(99), schedule.total_sleep(7)), (50, 30, 0));
+        assert_eq!(schedule.sleepiest_minute(10), Some((24, 2)));
+        assert_eq!(schedule.sleepiest_minute(99), Some((45, 3)));
+        assert_eq!(schedule.histogram(99).unwrap()[36..46], [1, 1, 1, 1, 2, 2, 2, 2, 2, 3]);
+        assert_eq!(schedule.shifts().len(), 5);
+        assert_eq!(schedule.shifts()[0].naps[1], (((1518, 11, 1), (0, 30)), ((1518, 11, 1), (0, 55))));
+        assert_eq!((schedule.shifts()[1].guard, schedule.shifts()[1].began), (99, ((1518, 11, 1), (23, 58))));
+
+        let big_ids = raw.replace("#10 ", "#1000000 ").replace("#99 ", "#4096 ");
+        assert_eq!(solve_part1(&input_generator(&big_ids)), 1000000 * 24);
+        assert_eq!(solve_part2(&input_generator(&big_ids)), 4096 * 45);
+
+        let largest = "[1518-11-01 00:00] Guard #4294967295 begins shift
+[1518-11-01 00:58] falls asleep
+[1518-11-01 00:59] wakes up";
+        assert_eq!(solve_part1(&input_generator(largest)), 4294967295 * 58);
+        assert_eq!(solve_part2(&input_generator(largest)), 4294967295 * 58);
+
+        let awake = GuardSchedule::new(&input_generator("[1518-11-01 00:00] Guard #10 begins shift
+[1518-11-02 00:00] Guard #99 begins shift")).unwrap();
+        assert_eq!((awake.strategy1(), awake.strategy2()), (None, None));
+    }
+
+    #[test]
+    fn timelines() {
+        let raw = examples::examples(4)[0].input;
+        let input = input_generator(raw);
+
+        assert_eq!(timeline(&input), "Date   ID   Minute
+            000000000011111111112222222222333333333344444444445555555555
+            012345678901234567890123456789012345678901234567890123456789
+11-01  #10  .....####################.....#########################.....
+11-02  #99  ........................................##########..........
+11-03  #10  ........................#####...............................
+11-04  #99  ....................................##########..............
+11-05  #99  .............................................##########.....");
+
+        let csv = timeline_csv(&input);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 6);
+        assert!(lines[0].starts_with("date,guard,0,1,2,"));
+        assert!(lines[0].ends_with(",58,59"));
+        assert!(lines[2].starts_with("1518-11-02,99,0,0,"));
+        assert_eq!(lines[2].split(',').skip(2).filter(|&minute| minute == "1").count(), 10);
+        assert_eq!(lines[2].split(',').nth(2 + 40), Some("1"));
+
+        // Shifts at the end of a month and of a year are on duty the next day
+        let input = input_generator("[1518-02-28 23:50] Guard #1234 begins shift
+[1518-12-31 23:59] Guard #5 begins shift");
+        assert_eq!(timeline(&input).lines().skip(3).collect::<Vec<&str>>(), vec![
+            format!("03-01  #1234  {}", ".".repeat(60)),
+            format!("01-01  #5     {}", ".".repeat(60))
+        ]);
+        assert!(timeline_csv(&input).ends_with(&format!("1519-01-01,5,{}", vec!["0"; 60].join(","))));
+    }
+
+    #[test]
+    fn merging() {
+        let first = "[1518-11-01 00:25] wakes up
+[1518-11-01 00:00] Guard #10 begins shift
+[1518-11-02 00:40] falls asleep";
+        let second = "[1518-11-01 00:05] falls asleep
+[1518-11-01 00:25] wakes up
+
+[1518-11-02 00:40] wakes up
+[1518-11-01 23:58] Guard #99 begins shift";
+        let third = "[1518-11-02 00:50] wakes up
+[1518-11-02 00:40] Guard #7 begins shift";
+
+        let (merged, conflicts) = merge_logs(vec![first, second, third]).unwrap();
+        assert_eq!(merged, input_generator("[1518-11-01 00:00] Guard #10 begins shift
 [1518-11-01 00:05] falls asleep
 [1518-11-01 00:25] wakes up
-[1518-11-01 00:30] falls asleep
-[1518-11-01 00:55] wakes up
 [1518-11-01 23:58] Guard #99 begins shift
 [1518-11-02 00:40] falls asleep
-[1518-11-02 00:50] wakes up
-[1518-11-03 00:05] Guard #10 begins shift
-[1518-11-03 00:24] falls asleep
-[1518-11-03 00:29] wakes up
-[1518-11-04 00:02] Guard #99 begins shift
-[1518-11-04 00:36] falls asleep
-[1518-11-04 00:46] wakes up
-[1518-11-05 00:03] Guard #99 begins shift
-[1518-11-05 00:45] falls asleep
-[1518-11-05 00:55] wakes up";
-        assert_eq!(solve_part1(&input_generator(raw)), 240);
-        assert_eq!(solve_part2(&input_generator(raw)), 4455);
+[1518-11-02 00:50] wakes up"));
+        assert_eq!(conflicts, vec![
+            (((1518, 11, 2), (0, 40)), vec![Observation::FallsAsleep, Observation::WakesUp, Observation::BeginsShift(7)])
+        ]);
+
+        let (merged, conflicts) = merge_logs(vec![first, first]).unwrap();
+        assert_eq!(merged.len(), 3);
+        assert!(conflicts.is_empty());
+
+        let malformed = merge_logs(vec![first, "[1518-11-01 00:05] falls asleep\n\n[1518-11-01 00:25] dozes off"]);
+        assert_eq!(malformed, Err(MalformedLine(1, 3, "[1518-11-01 00:25] dozes off".to_string())));
+        assert_eq!(malformed.unwrap_err().to_string(), "Malformed log entry in source 1 on line 3: \"[1518-11-01 00:25] dozes off\"");
+        assert!(merge_logs(vec!["[1518-11-01 00:00] Guard #x begins shift"]).is_err());
+        assert!(merge_logs(vec!["1518-11-01 00:00 wakes up"]).is_err());
+    }
+
+    #[test]
+    fn validation() {
+        let raw = "[1518-02-28 23:50] wakes up
+[1518-02-28 23:55] Guard #3 begins shift
+[1518-02-28 23:58] falls asleep
+[1518-03-01 00:02] falls asleep
+[1518-03-01 00:03] wakes up
+[1518-03-01 00:10] wakes up
+[1518-03-01 23:59] Guard #4 begins shift
+[1518-03-02 00:30] falls asleep";
+        let input = input_generator(raw);
+        assert_eq!(validate(&input), vec![
+            LogError::NoGuard(((1518, 2, 28), (23, 50))),
+            LogError::DoubleSleep(((1518, 3, 1), (0, 2))),
+            LogError::OrphanWake(((1518, 3, 1), (0, 10))),
+            LogError::UnfinishedNap(((1518, 3, 1), (23, 59)))
+        ]);
+        assert_eq!(
+            GuardSchedule::new(&input).err().map(|e| e.to_string()),
+            Some("Entry at 1518-02-28 23:50 comes before any guard began a shift".to_string())
+        );
+
+        // Naps across the hour, midnight and a leap day
+        let raw = "[1516-02-28 23:55] Guard #3 begins shift
+[1516-02-28 23:58] falls asleep
+[1516-03-01 00:02] wakes up
+[1518-11-01 00:00] Guard #5 begins shift
+[1518-11-01 00:50] falls asleep
+[1518-11-01 01:10] wakes up";
+        let input = input_generator(raw);
+        assert!(validate(&input).is_empty());
+
+        let schedule = GuardSchedule::new(&input).unwrap();
+        assert_eq!(schedule.total_sleep(3), 24 * 60 + 4);
+        assert_eq!(schedule.sleepiest_minute(3), Some((59, 25)));
+        assert_eq!(schedule.total_sleep(5), 20);
+        assert_eq!(schedule.histogram(5).unwrap()[..10], [1; 10]);
+        assert_eq!(schedule.histogram(5).unwrap()[50..], [1; 10]);
     }
 }
 
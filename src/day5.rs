@@ -1,5 +1,13 @@
 use std::boxed::Box;
 use std::cmp::min;
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+use memmap2::Mmap;
+use rayon::prelude::*;
 
 pub fn react_once(polymer: &str) -> Option<String> {
     let mut after = String::new();
@@ -29,58 +37,353 @@ pub fn react_once(polymer: &str) -> Option<String> {
     }
 }
 
-#[aoc(day5, part1)]
-pub fn solve_part1(input: &str) -> usize {
-    let mut out = input.trim().to_string();
+fn reacts(a: u8, b: u8) -> bool {
+    a != b && a.eq_ignore_ascii_case(&b)
+}
+
+// Reacts the polymer fully in a single pass: every unit either reacts with
+// the last unit still standing before it or stands itself. When a trace is
+// given, the positions of each reacting pair are added to it in the order
+// they reacted.
+pub fn react(polymer: &[u8], trace: Option<&mut Vec<(usize, usize)>>) -> Vec<u8> {
+    react_units(polymer, reacts, trace)
+}
+
+fn react_units<T: Copy, F: Fn(T, T) -> bool>(polymer: &[T], reacts: F, trace: Option<&mut Vec<(usize, usize)>>) -> Vec<T> {
+    let trace = match trace {
+        Some(trace) => trace,
+        // Without a trace only the units themselves need to be kept, which
+        // takes less memory than their positions
+        None => {
+            let mut stack: Vec<T> = Vec::new();
+
+            for &unit in polymer {
+                match stack.last() {
+                    Some(&last) if reacts(last, unit) => { stack.pop(); },
+                    _ => stack.push(unit)
+                }
+            }
+
+            return stack;
+        }
+    };
+
+    // Positions of the units still standing
+    let mut stack: Vec<usize> = Vec::new();
 
-    while let Some(after) = react_once(&out) {
-        out = after;
+    for (position, &unit) in polymer.iter().enumerate() {
+        match stack.last() {
+            Some(&last) if reacts(polymer[last], unit) => {
+                stack.pop();
+                trace.push((last, position));
+            },
+            _ => stack.push(position)
+        }
     }
 
-    out.len()
+    stack.into_iter().map(|position| polymer[position]).collect()
 }
 
-#[aoc(day5, part2)]
-pub fn solve_part2(input: &str) -> usize {
-    let mut uniq_chars = input
-        .trim()
-        .chars()
-        .map(|c| c.to_ascii_uppercase())
-        .collect::<Vec<char>>();
+// Joins two fully reacted polymers: only units at the seam can still react,
+// and each reaction exposes the next pair at the seam. This makes reacting
+// associative, so chunks can be reacted separately and joined in any
+// grouping.
+fn join_reacted(mut left: Vec<u8>, right: Vec<u8>) -> Vec<u8> {
+    let mut skip = 0;
+
+    while skip < right.len() && left.last().is_some_and(|&last| reacts(last, right[skip])) {
+        left.pop();
+        skip += 1;
+    }
+
+    left.extend_from_slice(&right[skip..]);
+    left
+}
+
+// Length of the fully reacted polymer, reacting chunks of it on the rayon
+// thread pool and joining the results
+pub fn react_parallel(polymer: &[u8], chunk_size: usize) -> usize {
+    react_chunks(polymer, chunk_size).len()
+}
+
+fn react_chunks(polymer: &[u8], chunk_size: usize) -> Vec<u8> {
+    polymer
+        .par_chunks(chunk_size.max(1))
+        .map(|chunk| react(chunk, None))
+        .reduce(Vec::new, join_reacted)
+}
+
+// Size of the chunks react_file reacts separately
+pub const FILE_CHUNK_SIZE: usize = 1 << 24;
+
+// Same as react_parallel for a polymer in a file, which is memory mapped
+// rather than read. The file is reacted a batch of chunks (one per thread) at
+// a time and each batch joined onto the polymer reacted so far, so only that
+// polymer and one reacted batch are held in memory at once.
+pub fn react_file<P: AsRef<Path>>(path: P) -> io::Result<usize> {
+    let file = fs::File::open(path)?;
+
+    // An empty file can't be mapped
+    if file.metadata()?.len() == 0 { return Ok(0) }
+
+    // Safe as long as the file isn't modified while mapped
+    let mapped = unsafe { Mmap::map(&file)? };
+    let polymer = mapped.trim_ascii();
+
+    let batch_size = FILE_CHUNK_SIZE * rayon::current_num_threads();
+
+    Ok(polymer
+        .chunks(batch_size)
+        .fold(Vec::new(), |reacted, batch| join_reacted(reacted, react_chunks(batch, FILE_CHUNK_SIZE)))
+        .len())
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct InvalidRule(pub usize, pub String);
+
+impl fmt::Display for InvalidRule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Invalid reaction rule on line {}: {:?}", self.0, self.1)
+    }
+}
+
+impl Error for InvalidRule {}
+
+// Which pairs of units react with each other. Units connected by rules are
+// of the same type, which is named after its smallest unit.
+#[derive(Debug, Clone)]
+pub struct ReactionRules {
+    pairs: HashSet<(char, char)>,
+    unit_types: HashMap<char, char>,
+    // Whether every unit reacts with at most one other unit, like the
+    // standard rules. Only then is the result the same whatever order the
+    // units react in.
+    single_partners: bool
+}
+
+impl ReactionRules {
+    // Same letter, opposite case
+    pub fn standard() -> ReactionRules {
+        ReactionRules::new((b'a'..=b'z').map(|c| (c as char, c.to_ascii_uppercase() as char)))
+    }
+
+    pub fn new<I: IntoIterator<Item = (char, char)>>(pairs: I) -> ReactionRules {
+        let pairs: HashSet<(char, char)> = pairs
+            .into_iter()
+            .flat_map(|(a, b)| vec![(a, b), (b, a)])
+            .collect();
+
+        let mut unit_types: HashMap<char, char> = HashMap::new();
+        let find = |unit_types: &HashMap<char, char>, mut unit: char| {
+            while let Some(&parent) = unit_types.get(&unit) {
+                if parent == unit { break }
+                unit = parent;
+            }
+
+            unit
+        };
+
+        for &(a, b) in &pairs {
+            let (root_a, root_b) = (find(&unit_types, a), find(&unit_types, b));
+            unit_types.insert(root_a.max(root_b), root_a.min(root_b));
+            unit_types.entry(root_a.min(root_b)).or_insert(root_a.min(root_b));
+        }
+
+        let mut partners: HashMap<char, usize> = HashMap::new();
+        for &(a, _) in &pairs { *partners.entry(a).or_default() += 1 }
+        let single_partners = partners.values().all(|&count| count == 1);
+
+        let units: Vec<char> = unit_types.keys().cloned().collect();
+        for unit in units {
+            let root = find(&unit_types, unit);
+            unit_types.insert(unit, root);
+        }
 
-    uniq_chars.sort();
-    uniq_chars.dedup();
+        ReactionRules { pairs, unit_types, single_partners }
+    }
 
-    let mut shortest = input.len();
+    // One pair of units per line separated by whitespace, for example "a A".
+    // Blank lines and lines starting with # are skipped.
+    pub fn parse(rules: &str) -> Result<ReactionRules, InvalidRule> {
+        let mut pairs = Vec::new();
 
-    let out = react_once(&input.trim()).unwrap();
+        for (number, line) in rules.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') { continue }
 
-    for c in uniq_chars {
-        let mut polymer = out
-            .replace(|x| x == c, "")
-            .replace(|x| x == c.to_ascii_lowercase(), "")
-            .to_string();
+            let units: Vec<Vec<char>> = line.split_whitespace().map(|unit| unit.chars().collect()).collect();
 
-        while let Some(after) = react_once(&polymer) {
-            polymer = after;
+            match units.as_slice() {
+                [a, b] if a.len() == 1 && b.len() == 1 => pairs.push((a[0], b[0])),
+                _ => return Err(InvalidRule(number + 1, line.to_string()))
+            }
         }
 
-        shortest = min(shortest, polymer.len());
+        Ok(ReactionRules::new(pairs))
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<ReactionRules> {
+        let rules = fs::read_to_string(path)?;
+        ReactionRules::parse(&rules).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    pub fn reacts(&self, a: char, b: char) -> bool {
+        self.pairs.contains(&(a, b))
+    }
+
+    // Units without any rule are a type of their own
+    pub fn unit_type(&self, unit: char) -> char {
+        self.unit_types.get(&unit).cloned().unwrap_or(unit)
+    }
+
+    // See react
+    pub fn react(&self, polymer: &[char], trace: Option<&mut Vec<(usize, usize)>>) -> Vec<char> {
+        react_units(polymer, |a, b| self.reacts(a, b), trace)
+    }
+
+    // The shortest polymer reachable by removing all units of one type
+    pub fn shortest_after_removal(&self, polymer: &[char]) -> usize {
+        let (reacted, impact) = self.removals(polymer);
+
+        impact
+            .into_iter()
+            .fold(reacted, |shortest, (_, length)| min(shortest, length))
+    }
+
+    // (unit type, length of the reacted polymer without it) for every type
+    // in the polymer, ordered by type
+    pub fn removal_impact(&self, polymer: &[char]) -> Vec<(char, usize)> {
+        self.removals(polymer).1
     }
 
-    shortest
+    // The length of the reacted polymer along with the removal impact. When
+    // every unit has a single partner, removing a type commutes with the
+    // reactions of other types, so each removal starts from the polymer
+    // already reacted once. Otherwise a reaction can take away a unit that
+    // would have reacted differently without the removed type, so each
+    // removal starts from the original polymer. The types are tried in
+    // parallel.
+    fn removals(&self, polymer: &[char]) -> (usize, Vec<(char, usize)>) {
+        let mut unit_types = polymer
+            .iter()
+            .map(|&unit| self.unit_type(unit))
+            .collect::<Vec<char>>();
+
+        unit_types.sort();
+        unit_types.dedup();
+
+        let reacted = self.react(polymer, None);
+        let start = if self.single_partners { &reacted[..] } else { polymer };
+
+        let impact = unit_types
+            .into_par_iter()
+            .map(|unit_type| {
+                let polymer: Vec<char> = start
+                    .iter()
+                    .cloned()
+                    .filter(|&unit| self.unit_type(unit) != unit_type)
+                    .collect();
+
+                (unit_type, self.react(&polymer, None).len())
+            })
+            .collect();
+
+        (reacted.len(), impact)
+    }
+}
+
+#[aoc(day5, part1)]
+pub fn solve_part1(input: &str) -> usize {
+    react(input.trim().as_bytes(), None).len()
+}
+
+#[aoc(day5, part2)]
+pub fn solve_part2(input: &str) -> usize {
+    ReactionRules::standard().shortest_after_removal(&input.trim().chars().collect::<Vec<char>>())
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{solve_part1, react_once};
+    use super::{solve_part1, solve_part2, react, react_file, react_once, react_parallel, InvalidRule, ReactionRules};
+    use examples;
+    use std::env;
+    use std::fs;
     #[test]
     fn examples() {
         assert_eq!(react_once("bAaB"), Some("bB".to_string()));
         assert_eq!(react_once("bB"), Some("".to_string()));
-        assert_eq!(solve_part1("dabAcCaCBAcCcaDA"), 10);
+        assert_eq!(solve_part1(examples::examples(5)[0].input), 10);
         assert_eq!(solve_part1("abBA"), 0);
         assert_eq!(solve_part1("aaA"), 1);
+        assert_eq!(solve_part2(examples::examples(5)[0].input), 4);
+
+        let mut trace = Vec::new();
+        assert_eq!(react(examples::examples(5)[0].input.as_bytes(), Some(&mut trace)), b"dabCBAcaDA");
+        assert_eq!(trace, vec![(4, 5), (3, 6), (10, 11)]);
+        assert_eq!(react(b"abBA", None), b"");
+    }
+
+    #[test]
+    fn parallel() {
+        let polymer = examples::examples(5)[0].input.as_bytes();
+
+        for chunk_size in 1..=polymer.len() + 1 {
+            assert_eq!(react_parallel(polymer, chunk_size), 10);
+        }
+
+        // Reactions cascading across many chunk boundaries
+        let nested: Vec<u8> = b"abcdefgh".iter().cloned().chain(b"HGFEDCBA".iter().cloned()).cycle().take(1600).collect();
+        assert_eq!(react_parallel(&nested, 3), 0);
+        assert_eq!(react_parallel(&nested[5..], 7), 5);
+        assert_eq!(react_parallel(b"", 4), 0);
+
+        let path = env::temp_dir().join(format!("aoc-day5-test-{}", ::std::process::id()));
+        fs::write(&path, "dabAcCaCBAcCcaDA\n").unwrap();
+        assert_eq!(react_file(&path).unwrap(), 10);
+        fs::write(&path, "").unwrap();
+        assert_eq!(react_file(&path).unwrap(), 0);
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn rules() {
+        let chars = |s: &str| s.chars().collect::<Vec<char>>();
+        let standard = ReactionRules::standard();
+        assert_eq!(standard.react(&chars(examples::examples(5)[0].input), None), chars("dabCBAcaDA"));
+        assert_eq!(standard.unit_type('Q'), 'Q');
+        assert_eq!(standard.unit_type('q'), 'Q');
+
+        let rules = ReactionRules::parse("# opposites
+ö Ö
+
++ -
+- ~
+").unwrap();
+        assert!(rules.reacts('Ö', 'ö') && rules.reacts('~', '-') && !rules.reacts('+', '~'));
+        assert!(!rules.reacts('a', 'A'));
+        assert_eq!((rules.unit_type('~'), rules.unit_type('-'), rules.unit_type('x')), ('+', '+', 'x'));
+
+        let mut trace = Vec::new();
+        assert_eq!(rules.react(&chars("xö+-Öx~-"), Some(&mut trace)), chars("xx"));
+        assert_eq!(trace, vec![(2, 3), (1, 4), (6, 7)]);
+
+        // Removing x lets the ö units react
+        assert_eq!(rules.shortest_after_removal(&chars("öx+-Öa~")), 2);
+        assert_eq!(rules.removal_impact(&chars("öx+-Öa~")), vec![('+', 4), ('a', 4), ('x', 2), ('Ö', 3)]);
+        assert_eq!(standard.removal_impact(&chars(examples::examples(5)[0].input)), vec![('A', 6), ('B', 8), ('C', 4), ('D', 6)]);
+        assert_eq!(standard.removal_impact(&chars("aA")), vec![('A', 0)]);
+        assert_eq!(standard.shortest_after_removal(&[]), 0);
+
+        // Overlapping pairs: reacting b with c first strands a and d, which
+        // react once x is gone
+        let chain = ReactionRules::parse("a b\nb c\nc d").unwrap();
+        assert_eq!(chain.react(&chars("abcd"), None), chars(""));
+        assert_eq!(chain.react(&chars("axbcd"), None), chars("axd"));
+        assert_eq!(chain.shortest_after_removal(&chars("axbcd")), 0);
+        assert_eq!(chain.removal_impact(&chars("axbcd")), vec![('a', 1), ('x', 0)]);
+
+        assert_eq!(ReactionRules::parse("a A\nab c").err(), Some(InvalidRule(2, "ab c".to_string())));
+        assert_eq!(ReactionRules::parse("a").err().map(|e| e.to_string()), Some("Invalid reaction rule on line 1: \"a\"".to_string()));
     }
 }
 
@@ -1,21 +1,18 @@
-// TODO: k-d tree?
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
 
-use std::cmp::Ordering;
+use rayon::prelude::*;
 
-type Pair = (usize, usize);
-type Point = (usize, Pair);
-
-#[derive(Clone, Debug)]
-enum Place {
-    Empty,
-    Indeterminate(usize),
-    DistanceFrom(usize, usize)
-}
+pub type Pair = (isize, isize);
+pub type Point = (usize, Pair);
+// Wide enough for the distance between any two points, and Euclidean
+// distances squared between all but the furthest apart
+pub type Rank = u128;
 
 fn parse_line(line: &str) -> Pair {
     let mut it = line
         .split(", ")
-        .map(|s| s.parse::<usize>().unwrap());
+        .map(|s| s.parse::<isize>().unwrap());
 
     (it.next().unwrap(), it.next().unwrap())
 }
@@ -29,104 +26,505 @@ pub fn input_generator(input: &str) -> Vec<Point> {
         .collect::<Vec<Point>>()
 }
 
-fn distance(a: Pair, b: Pair) -> usize {
-    let (x1, y1) = a;
-    let (x2, y2) = b;
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Metric {
+    Manhattan,
+    Chebyshev,
+    Euclidean
+}
 
-    let distance_x = (x1 as isize - x2 as isize).abs() as usize;
-    let distance_y = (y1 as isize - y2 as isize).abs() as usize;
-    distance_x + distance_y
+impl Metric {
+    // Orders pairs of points exactly like their distance. Euclidean distances
+    // are squared so that ties are never lost to rounding.
+    pub fn rank(&self, a: Pair, b: Pair) -> Rank {
+        let (dx, dy) = (a.0.abs_diff(b.0) as Rank, a.1.abs_diff(b.1) as Rank);
+
+        match *self {
+            Metric::Manhattan => dx + dy,
+            Metric::Chebyshev => dx.max(dy),
+            Metric::Euclidean => (dx * dx).checked_add(dy * dy).expect("Points too far apart to compare")
+        }
+    }
+
+    pub fn distance(&self, a: Pair, b: Pair) -> f64 {
+        match *self {
+            Metric::Euclidean => (self.rank(a, b) as f64).sqrt(),
+            _ => self.rank(a, b) as f64
+        }
+    }
+
+    // Lowest rank of two points this far apart along one axis
+    fn axis_rank(&self, gap: usize) -> Rank {
+        let gap = gap as Rank;
+
+        match *self {
+            Metric::Euclidean => gap * gap,
+            _ => gap
+        }
+    }
 }
 
-#[aoc(day6, part1)]
-pub fn solve_part1(input: &[Point]) -> isize {
-    let max_x = *input.iter().map(|(_, (x, _))| x).max().unwrap() + 2;
-    let max_y = *input.iter().map(|(_, (_, y))| y).max().unwrap() + 2;
-    let mut board: Vec<Vec<Place>> = vec![vec![Place::Empty; max_x + 1]; max_y + 1];
-
-    for &point in input.iter() {
-        let (index, (point_x, point_y)) = point;
-        for x in 0..=max_x {
-            for y in 0..=max_y {
-                let distance = distance((point_x, point_y), (x, y));
-
-                match board[y][x] {
-                    Place::Empty => board[y][x] = Place::DistanceFrom(index, distance),
-                    Place::Indeterminate(at_distance) => {
-                        if distance < at_distance {
-                            board[y][x] = Place::DistanceFrom(index, distance)
-                        }
-                    },
-                    Place::DistanceFrom(_index, existing_distance) => {
-                        match distance.cmp(&existing_distance) {
-                            Ordering::Less =>
-                                board[y][x] = Place::DistanceFrom(index, distance),
-                            Ordering::Equal => board[y][x] = Place::Indeterminate(distance),
-                            Ordering::Greater => ()
-                        }
-                    }
-                }
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Nearest {
+    // (index of the site, rank)
+    Site(usize, Rank),
+    // More than one site at this rank
+    Tie(Rank)
+}
+
+impl Nearest {
+    pub fn rank(&self) -> Rank {
+        match *self {
+            Nearest::Site(_, rank) | Nearest::Tie(rank) => rank
+        }
+    }
+}
+
+// 2-d tree over the sites. Every range of sites is ordered so that its
+// middle site splits the rest by x (at even depths) or y (at odd depths).
+pub struct KdTree {
+    sites: Vec<Point>,
+    metric: Metric
+}
+
+fn coordinate(pair: Pair, axis: usize) -> isize {
+    if axis == 0 { pair.0 } else { pair.1 }
+}
+
+fn build(sites: &mut [Point], axis: usize) {
+    if sites.len() <= 1 { return }
+
+    let middle = sites.len() / 2;
+    sites.select_nth_unstable_by_key(middle, |&(_, pair)| coordinate(pair, axis));
+
+    let (before, after) = sites.split_at_mut(middle);
+    build(before, 1 - axis);
+    build(&mut after[1..], 1 - axis);
+}
+
+fn search(sites: &[Point], axis: usize, at: Pair, metric: Metric, best: &mut Option<Nearest>) {
+    if sites.is_empty() { return }
+
+    let middle = sites.len() / 2;
+    let (index, site) = sites[middle];
+    let site_rank = metric.rank(site, at);
+
+    *best = match *best {
+        Some(nearest) if site_rank > nearest.rank() => Some(nearest),
+        Some(nearest) if site_rank == nearest.rank() => Some(Nearest::Tie(site_rank)),
+        _ => Some(Nearest::Site(index, site_rank))
+    };
+
+    let (near, far) = if coordinate(at, axis) < coordinate(site, axis) {
+        (&sites[..middle], &sites[middle + 1..])
+    } else {
+        (&sites[middle + 1..], &sites[..middle])
+    };
+
+    search(near, 1 - axis, at, metric, best);
+
+    // Sites on the far side are at least as far away as the split, and
+    // those exactly that far away could still tie
+    let split = metric.axis_rank(coordinate(at, axis).abs_diff(coordinate(site, axis)));
+    if best.is_none_or(|nearest| split <= nearest.rank()) {
+        search(far, 1 - axis, at, metric, best);
+    }
+}
+
+impl KdTree {
+    pub fn new(input: &[Point], metric: Metric) -> KdTree {
+        let mut sites = input.to_vec();
+        build(&mut sites, 0);
+        KdTree { sites, metric }
+    }
+
+    // None if there are no sites
+    pub fn nearest(&self, at: Pair) -> Option<Nearest> {
+        let mut best = None;
+        search(&self.sites, 0, at, self.metric, &mut best);
+        best
+    }
+}
+
+type Corner = (i128, i128);
+
+fn cross(o: Corner, a: Corner, b: Corner) -> i128 {
+    let ((ax, ay), (bx, by)) = ((a.0 - o.0, a.1 - o.1), (b.0 - o.0, b.1 - o.1));
+
+    ax.checked_mul(by)
+        .zip(ay.checked_mul(bx))
+        .and_then(|(first, second)| first.checked_sub(second))
+        .expect("Points too far apart to compare")
+}
+
+// Whether each site lies on the boundary of the convex hull of all sites,
+// corners and points along its edges alike
+fn on_convex_hull(input: &[Point]) -> Vec<bool> {
+    let corner = |(x, y): Pair| (x as i128, y as i128);
+    let mut points: Vec<Corner> = input.iter().map(|&(_, site)| corner(site)).collect();
+    points.sort_unstable();
+    points.dedup();
+
+    // Monotone chain, dropping collinear points
+    let chain = |points: &mut dyn Iterator<Item=&Corner>| {
+        let mut chain: Vec<Corner> = Vec::new();
+
+        for &point in points {
+            while chain.len() >= 2 && cross(chain[chain.len() - 2], chain[chain.len() - 1], point) <= 0 {
+                chain.pop();
             }
+
+            chain.push(point);
         }
+
+        chain.pop();
+        chain
+    };
+
+    let mut hull = chain(&mut points.iter());
+    hull.extend(chain(&mut points.iter().rev()));
+    if hull.is_empty() { hull = points }
+
+    input
+        .iter()
+        .map(|&(_, site)| {
+            let s = corner(site);
+
+            (0..hull.len()).any(|i| {
+                let (a, b) = (hull[i], hull[(i + 1) % hull.len()]);
+
+                cross(a, b, s) == 0 &&
+                    a.0.min(b.0) <= s.0 && s.0 <= a.0.max(b.0) &&
+                    a.1.min(b.1) <= s.1 && s.1 <= a.1.max(b.1)
+            })
+        })
+        .collect()
+}
+
+// Whether each point has no other point at or beyond it in both coordinates
+fn undominated(points: &[Corner]) -> Vec<bool> {
+    let mut order: Vec<usize> = (0..points.len()).collect();
+    order.sort_unstable_by_key(|&i| Reverse(points[i]));
+
+    let mut result = vec![false; points.len()];
+    // Highest second coordinate among the points further along the first
+    let mut highest: Option<i128> = None;
+    let mut start = 0;
+
+    while start < order.len() {
+        let top = points[order[start]];
+        let end = start + order[start..].iter().take_while(|&&i| points[i].0 == top.0).count();
+
+        // Only the highest point of a column can escape, and only alone
+        let alone = end == start + 1 || points[order[start + 1]].1 < top.1;
+        result[order[start]] = alone && highest.is_none_or(|highest| highest < top.1);
+        highest = Some(highest.map_or(top.1, |highest| highest.max(top.1)));
+
+        start = end;
     }
 
-    let mut area: Vec<isize> = vec![0; input.len()];
+    result
+}
+
+// Whether each site (in input order) is strictly nearest to infinitely many
+// points. Under Manhattan distance that's when no other site lies in one of
+// the quarter planes to the east, north, west or south of it bounded by the
+// diagonals (edges included), as points far off that way stay nearest to it.
+// In terms of x + y and x - y, the site is then extreme in one of the four
+// diagonal directions. Chebyshev distance is Manhattan distance turned by 45
+// degrees, so there the quarter planes are the quadrants and x and y are used
+// as they are. Under Euclidean distance it's the sites on the convex hull. A
+// site shared by two entries is nearest to no point.
+pub fn infinite_regions(input: &[Point], metric: Metric) -> Vec<bool> {
+    if metric == Metric::Euclidean {
+        let mut entries: HashMap<Pair, usize> = HashMap::new();
+        for &(_, site) in input { *entries.entry(site).or_default() += 1 }
 
-    for x in 0..=max_x {
-        for y in 0..=max_y {
-            let at_edge = x == 0 || x == max_x || y == 0 || y == max_y;
+        return on_convex_hull(input)
+            .into_iter()
+            .zip(input)
+            .map(|(on_hull, (_, site))| on_hull && entries[site] == 1)
+            .collect();
+    }
 
-            match board[y][x] {
-                Place::DistanceFrom(index, _) => {
-                    if at_edge {
-                        area[index] = -1;
-                    }
-                    else if area[index] > -1 {
-                        area[index] += 1;
-                    }
-                },
-                _ => (),
+    // Wide enough that the diagonals can't overflow
+    let axes: Vec<Corner> = input
+        .iter()
+        .map(|&(_, (x, y))| (x as i128, y as i128))
+        .map(|(x, y)| if metric == Metric::Manhattan { (x + y, x - y) } else { (x, y) })
+        .collect();
+    let mut infinite = vec![false; input.len()];
+
+    for &(sign_a, sign_b) in &[(1, 1), (1, -1), (-1, 1), (-1, -1)] {
+        let turned: Vec<Corner> = axes.iter().map(|&(a, b)| (sign_a * a, sign_b * b)).collect();
+
+        for (infinite, extreme) in infinite.iter_mut().zip(undominated(&turned)) {
+            *infinite |= extreme;
+        }
+    }
+
+    infinite
+}
+
+// Bounding box of the cell of a site with a finite Euclidean region, found by
+// cutting a square around the site down by the bisector towards every other
+// site. While the cell reaches the edge of the square it could reach further,
+// so the square is doubled until it doesn't. Starting small keeps the
+// rounding errors in proportion to the size of the cell.
+fn cell_bounds(input: &[Point], site: Pair) -> (Pair, Pair) {
+    let mut reach = 1.0;
+
+    let cell = loop {
+        // Relative to the site
+        let mut cell = vec![(-reach, -reach), (reach, -reach), (reach, reach), (-reach, reach)];
+
+        for &(_, other) in input {
+            let (dx, dy) = ((other.0 as i128 - site.0 as i128) as f64, (other.1 as i128 - site.1 as i128) as f64);
+            if dx == 0.0 && dy == 0.0 { continue }
+
+            let limit = (dx * dx + dy * dy) / 2.0;
+            let side = |(x, y): (f64, f64)| x * dx + y * dy - limit;
+            let mut clipped = Vec::with_capacity(cell.len() + 1);
+
+            for i in 0..cell.len() {
+                let (a, b) = (cell[i], cell[(i + 1) % cell.len()]);
+                let (side_a, side_b) = (side(a), side(b));
+
+                if side_a <= 0.0 { clipped.push(a) }
+                if (side_a < 0.0 && side_b > 0.0) || (side_a > 0.0 && side_b < 0.0) {
+                    let t = side_a / (side_a - side_b);
+                    clipped.push((a.0 + t * (b.0 - a.0), a.1 + t * (b.1 - a.1)));
+                }
             }
+
+            cell = clipped;
+        }
+
+        if cell.iter().all(|&(x, y)| x.abs().max(y.abs()) < reach) { break cell }
+        reach *= 2.0;
+    };
+
+    // Points beyond the range of coordinates can't be nearest to anything
+    let bound = |offset: f64, origin: isize| origin.saturating_add(offset as isize);
+    let low_x = cell.iter().map(|p| p.0).fold(f64::INFINITY, f64::min).floor() - 1.0;
+    let high_x = cell.iter().map(|p| p.0).fold(f64::NEG_INFINITY, f64::max).ceil() + 1.0;
+    let low_y = cell.iter().map(|p| p.1).fold(f64::INFINITY, f64::min).floor() - 1.0;
+    let high_y = cell.iter().map(|p| p.1).fold(f64::NEG_INFINITY, f64::max).ceil() + 1.0;
+
+    ((bound(low_x, site.0), bound(low_y, site.1)), (bound(high_x, site.0), bound(high_y, site.1)))
+}
+
+fn finite_area(tree: &KdTree, input: &[Point], (index, site): Point) -> usize {
+    let owns = |at: Pair| match tree.nearest(at) {
+        Some(Nearest::Site(nearest, _)) => nearest == index,
+        _ => false
+    };
+
+    if tree.metric == Metric::Euclidean {
+        let ((min_x, min_y), (max_x, max_y)) = cell_bounds(input, site);
+        return (min_y..=max_y).map(|y| (min_x..=max_x).filter(|&x| owns((x, y))).count()).sum();
+    }
+
+    // A step from a point towards its site brings it one closer to the site
+    // and at most one closer to any other, so regions are connected through
+    // steps along the axes (Manhattan) or also the diagonals (Chebyshev)
+    let steps: &[(isize, isize)] = if tree.metric == Metric::Manhattan {
+        &[(1, 0), (-1, 0), (0, 1), (0, -1)]
+    } else {
+        &[(1, 0), (-1, 0), (0, 1), (0, -1), (1, 1), (1, -1), (-1, 1), (-1, -1)]
+    };
+
+    if !owns(site) { return 0 }
+
+    let mut seen = HashSet::new();
+    seen.insert(site);
+    let mut queue = vec![site];
+    let mut area = 0;
+
+    while let Some((x, y)) = queue.pop() {
+        area += 1;
+
+        for &(dx, dy) in steps {
+            let next = match (x.checked_add(dx), y.checked_add(dy)) {
+                (Some(nx), Some(ny)) => (nx, ny),
+                _ => continue
+            };
+
+            if seen.insert(next) && owns(next) { queue.push(next) }
         }
     }
 
-    *area.iter().max().unwrap()
+    area
+}
+
+// Number of points strictly nearest to each site (in input order), or None
+// for the sites nearest to infinitely many points
+pub fn areas(input: &[Point], metric: Metric) -> Vec<Option<usize>> {
+    let tree = KdTree::new(input, metric);
+
+    input
+        .par_iter()
+        .zip(infinite_regions(input, metric))
+        .map(|(&point, infinite)| if infinite { None } else { Some(finite_area(&tree, input, point)) })
+        .collect()
+}
+
+#[aoc(day6, part1)]
+pub fn solve_part1(input: &[Point]) -> isize {
+    areas(input, Metric::Manhattan)
+        .into_iter()
+        .map(|area| area.map_or(-1, |area| area as isize))
+        .max()
+        .unwrap()
 }
 
 #[aoc(day6, part2)]
 pub fn solve_part2(input: &[Point]) -> isize {
-    let max_x = *input.iter().map(|(_, (x, _))| x).max().unwrap() + 2;
-    let max_y = *input.iter().map(|(_, (_, y))| y).max().unwrap() + 2;
+    inner_part2(input, 10000)
+}
 
-    let mut region_size = 0;
+pub fn inner_part2(input: &[Point], max_total_distance: usize) -> isize {
+    total_distance_region(input, Metric::Manhattan, max_total_distance)
+}
 
-    for x in 0..=max_x {
-        for y in 0..=max_y {
-            let total_distance = input
-                .iter()
-                .map(|(_, place)| distance((x, y), *place))
-                .sum::<usize>();
+// Number of points whose total distance to all sites is below the limit. A
+// point further than limit / sites outside the bounding box of the sites along
+// either axis is at least that far from every site, so only the box grown by
+// that much is checked, a row at a time in parallel.
+pub fn total_distance_region(input: &[Point], metric: Metric, max_total_distance: usize) -> isize {
+    if input.is_empty() { return 0 }
 
-            if total_distance < 10000 { region_size += 1 }
-        }
-    }
+    let margin = (max_total_distance / input.len()) as isize;
+    let min_x = input.iter().map(|&(_, (x, _))| x).min().unwrap().saturating_sub(margin);
+    let max_x = input.iter().map(|&(_, (x, _))| x).max().unwrap().saturating_add(margin);
+    let min_y = input.iter().map(|&(_, (_, y))| y).min().unwrap().saturating_sub(margin);
+    let max_y = input.iter().map(|&(_, (_, y))| y).max().unwrap().saturating_add(margin);
+
+    let within = |at: Pair| match metric {
+        Metric::Euclidean => input.iter().map(|&(_, site)| metric.distance(site, at)).sum::<f64>() < max_total_distance as f64,
+        _ => input.iter().map(|&(_, site)| metric.rank(site, at)).sum::<Rank>() < max_total_distance as Rank
+    };
 
-    region_size
+    (min_y..=max_y)
+        .into_par_iter()
+        .map(|y| (min_x..=max_x).filter(|&x| within((x, y))).count())
+        .sum::<usize>() as isize
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{input_generator, solve_part1};
+    use super::{input_generator, solve_part1, inner_part2, areas, infinite_regions, total_distance_region, KdTree, Metric, Nearest};
+    use examples;
     #[test]
     fn examples() {
-        let input = "1, 1
-1, 6
-8, 3
-3, 4
-5, 5
-8, 9";
+        let input = examples::examples(6)[0].input;
         let parsed = input_generator(input);
         assert_eq!(solve_part1(&parsed), 17);
+        assert_eq!(inner_part2(&parsed, 32), 16);
+
+        let tree = KdTree::new(&parsed, Metric::Manhattan);
+        assert_eq!(tree.nearest((0, 0)), Some(Nearest::Site(0, 2)));
+        assert_eq!(tree.nearest((5, 2)), Some(Nearest::Site(4, 3)));
+        assert_eq!(tree.nearest((0, 4)), Some(Nearest::Tie(3)));
+        assert_eq!(KdTree::new(&[], Metric::Manhattan).nearest((0, 0)), None);
+
+        assert_eq!(areas(&parsed, Metric::Manhattan), vec![None, None, None, Some(9), Some(17), None]);
+        assert_eq!(areas(&parsed, Metric::Chebyshev), vec![None, None, None, Some(8), Some(10), None]);
+        assert_eq!(areas(&parsed, Metric::Euclidean), vec![None, None, None, Some(10), Some(16), None]);
+        assert_eq!(total_distance_region(&parsed, Metric::Chebyshev, 32), 80);
+        assert_eq!(total_distance_region(&parsed, Metric::Euclidean, 32), 62);
+
+        // The same sites moved past the origin
+        let shifted = input_generator("-4, -9
+-4, -4
+3, -7
+-2, -6
+0, -5
+3, -1");
+        assert_eq!(solve_part1(&shifted), 17);
+        assert_eq!(inner_part2(&shifted, 32), 16);
+        assert_eq!(areas(&shifted, Metric::Euclidean), areas(&parsed, Metric::Euclidean));
+
+        // Far from the origin, with one site much further away still
+        let far: Vec<(usize, (isize, isize))> = parsed
+            .iter()
+            .cloned()
+            .chain(vec![(6, (4, -1000000))])
+            .map(|(i, (x, y))| (i, (x + 1000000000000, y - 1000000000000)))
+            .collect();
+        assert_eq!(areas(&far, Metric::Euclidean), vec![None, None, None, Some(10), Some(16), None, None]);
+        assert_eq!(areas(&far, Metric::Manhattan)[3..5], [Some(9), Some(17)]);
+
+        // Diagonals and squared distances that don't fit in 64 bits
+        let huge = 1isize << 62;
+        let extremes = vec![(0, (huge, huge)), (1, (-huge, -huge)), (2, (0, 0))];
+        assert_eq!(infinite_regions(&extremes, Metric::Manhattan), vec![true, true, false]);
+        assert_eq!(infinite_regions(&extremes, Metric::Euclidean), vec![true, true, true]);
+        assert_eq!(KdTree::new(&extremes, Metric::Euclidean).nearest((huge, -huge)), Some(Nearest::Site(2, 1 << 125)));
+
+        // Far above a line of sites, the middle one ties with its neighbours
+        // only under Chebyshev distance. Shared sites are never nearest.
+        let line = input_generator("0, 0
+2, 0
+4, 0
+4, 0");
+        assert_eq!(infinite_regions(&line, Metric::Manhattan), vec![true, true, false, false]);
+        assert_eq!(infinite_regions(&line, Metric::Chebyshev), vec![true, false, false, false]);
+        assert_eq!(infinite_regions(&line, Metric::Euclidean), vec![true, true, false, false]);
+    }
+
+    #[test]
+    fn nearest_matches_brute_force() {
+        // Small pseudo-random sites, many of them tied
+        let mut seed = 12345u64;
+        let mut next = || { seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407); (seed >> 33) as isize % 40 };
+        let sites: Vec<(usize, (isize, isize))> = (0..60).map(|i| (i, (next(), next()))).collect();
+
+        for &metric in &[Metric::Manhattan, Metric::Chebyshev, Metric::Euclidean] {
+            let tree = KdTree::new(&sites, metric);
+
+            for x in 0..45 {
+                for y in 0..45 {
+                    let closest = sites.iter().map(|&(_, site)| metric.rank(site, (x, y))).min().unwrap();
+                    let at_closest: Vec<usize> = sites.iter().filter(|&&(_, site)| metric.rank(site, (x, y)) == closest).map(|&(i, _)| i).collect();
+                    let expected = if at_closest.len() == 1 { Nearest::Site(at_closest[0], closest) } else { Nearest::Tie(closest) };
+                    assert_eq!(tree.nearest((x, y)), Some(expected));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn areas_match_brute_force() {
+        // A few sites close together around the origin of a large grid, so
+        // that infinite regions are the ones reaching its edge
+        let mut seed = 54321u64;
+        let mut next = || { seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407); (seed >> 33) as isize % 7 - 3 };
+
+        for _ in 0..10 {
+            let sites: Vec<(usize, (isize, isize))> = (0..6).map(|i| (i, (next(), next()))).collect();
+
+            for &metric in &[Metric::Manhattan, Metric::Chebyshev, Metric::Euclidean] {
+                let mut expected = vec![Some(0); sites.len()];
+
+                for x in -103..=103 {
+                    for y in -103..=103 {
+                        let ranks: Vec<u128> = sites.iter().map(|&(_, site)| metric.rank(site, (x, y))).collect();
+                        let closest = *ranks.iter().min().unwrap();
+                        if ranks.iter().filter(|&&rank| rank == closest).count() > 1 { continue }
+
+                        let index = ranks.iter().position(|&rank| rank == closest).unwrap();
+                        expected[index] = if x.abs() == 103 || y.abs() == 103 {
+                            None
+                        } else {
+                            expected[index].map(|area| area + 1)
+                        };
+                    }
+                }
+
+                assert_eq!(areas(&sites, metric), expected, "{:?} {:?}", metric, sites);
+            }
+        }
     }
 }
 
@@ -166,15 +166,10 @@ pub fn inner_part2(input: &Requirements, concurrency: usize, additional: usize)
 #[cfg(test)]
 mod tests {
     use super::{input_generator, solve_part1, inner_part2};
+    use examples;
     #[test]
     fn examples() {
-        let input = "Step C must be finished before step A can begin.
-Step C must be finished before step F can begin.
-Step A must be finished before step B can begin.
-Step A must be finished before step D can begin.
-Step B must be finished before step E can begin.
-Step D must be finished before step E can begin.
-Step F must be finished before step E can begin.";
+        let input = examples::examples(7)[0].input;
         let parsed = input_generator(input);
         assert_eq!(solve_part1(&parsed), "CABDFE");
         assert_eq!(inner_part2(&parsed, 2, 0), 15);
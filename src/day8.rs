@@ -52,9 +52,10 @@ pub fn solve_part2(input: &VecDeque<usize>) -> usize {
 #[cfg(test)]
 mod tests {
     use super::{input_generator, solve_part1, solve_part2};
+    use examples;
     #[test]
     fn examples() {
-        let raw = "2 3 0 3 10 11 12 1 1 0 1 99 2 1 1 2";
+        let raw = examples::examples(8)[0].input;
         let input = input_generator(raw);
         assert_eq!(solve_part1(&input), 138);
         assert_eq!(solve_part2(&input), 66);
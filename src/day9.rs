@@ -1,5 +1,7 @@
 use std::boxed::Box;
 use std::collections::VecDeque;
+use simulation::Simulation;
+use numeric::{add_score, checked, Overflow, Score};
 
 // TODO: separate list for elements moved from back?
 
@@ -12,15 +14,38 @@ pub fn input_generator(input: &str) -> Box<(usize, usize)> {
     Box::new((num_players, highest_marble))
 }
 
-#[aoc(day9, part1)]
-pub fn solve_part1(input: &(usize, usize)) -> usize {
-    let mut board: VecDeque<usize> = VecDeque::new();
-    board.push_back(0);
-    let (num_players, highest_marble) = *input;
-    let mut scores = vec![0; num_players];
+pub struct Game {
+    board: VecDeque<usize>,
+    scores: Vec<Score>,
+    marble: usize,
+    highest_marble: usize,
+    overflow: Option<Overflow>
+}
+
+impl Game {
+    pub fn new(num_players: usize, highest_marble: usize) -> Game {
+        let mut board: VecDeque<usize> = VecDeque::new();
+        board.push_back(0);
+
+        Game { board, scores: vec![Score::from(0usize); num_players], marble: 1, highest_marble, overflow: None }
+    }
+
+    pub fn high_score(&self) -> Result<Score, Overflow> {
+        match self.overflow {
+            Some(ref overflow) => Err(overflow.clone()),
+            None => Ok(self.scores.iter().max().unwrap().clone())
+        }
+    }
+}
+
+impl Simulation for Game {
+    type State = Vec<Score>;
 
-    for marble in 1..=highest_marble {
-        let player = (marble - 1) % num_players;
+    // Plays the next marble
+    fn step(&mut self) {
+        let board = &mut self.board;
+        let marble = self.marble;
+        let player = (marble - 1) % self.scores.len();
 
         if (marble % 23) > 0 {
             let popped = board.pop_front().unwrap();
@@ -34,28 +59,71 @@ pub fn solve_part1(input: &(usize, usize)) -> usize {
             }
 
             let picked_out = board.pop_back().unwrap();
-            scores[player] += marble + picked_out;
+            let score = match checked(marble.checked_add(picked_out), "a score") {
+                Ok(points) => add_score(&self.scores[player], points),
+                Err(overflow) => Err(overflow)
+            };
+
+            match score {
+                Ok(score) => self.scores[player] = score,
+                Err(overflow) => self.overflow = Some(overflow)
+            }
+
             let popped = board.pop_front().unwrap();
             board.push_back(popped)
         }
+
+        self.marble += 1;
+    }
+
+    // Also stops as soon as a score overflows
+    fn is_finished(&self) -> bool {
+        self.overflow.is_some() || self.marble > self.highest_marble
     }
 
-    *scores.iter().max().unwrap()
+    fn state(&self) -> &Vec<Score> {
+        &self.scores
+    }
+}
+
+pub fn high_score(num_players: usize, highest_marble: usize) -> Result<Score, Overflow> {
+    let mut game = Game::new(num_players, highest_marble);
+    game.run();
+    game.high_score()
+}
+
+#[aoc(day9, part1)]
+pub fn solve_part1(input: &(usize, usize)) -> Score {
+    let (num_players, highest_marble) = *input;
+    high_score(num_players, highest_marble).unwrap_or_else(|e| panic!("{}", e))
 }
 
 #[aoc(day9, part2)]
-pub fn solve_part2(input: &(usize, usize)) -> usize {
+pub fn solve_part2(input: &(usize, usize)) -> Score {
     let (num_players, highest_marble) = *input;
-    solve_part1(&(num_players, highest_marble * 100))
+
+    checked(highest_marble.checked_mul(100), "the last marble")
+        .and_then(|highest_marble| high_score(num_players, highest_marble))
+        .unwrap_or_else(|e| panic!("{}", e))
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{solve_part1};
+    use super::{input_generator, solve_part1, Game};
+    use examples;
+    use simulation::Simulation;
+    use numeric::Score;
+
     #[test]
     fn examples() {
-        assert_eq!(solve_part1(&(9, 25)), 32);
-        assert_eq!(solve_part1(&(10, 1618)), 8317);
+        let example = |i: usize| input_generator(examples::examples(9)[i].input);
+        assert_eq!(solve_part1(&example(0)), Score::from(32usize));
+        assert_eq!(solve_part1(&example(1)), Score::from(8317usize));
+
+        let mut game = Game::new(9, 25);
+        assert_eq!(game.run_until(|scores| scores.iter().any(|s| *s > Score::from(0usize))), Some(23));
+        assert_eq!(game.run(), 2);
+        assert_eq!(game.high_score(), Ok(Score::from(32usize)));
     }
 }
 
@@ -0,0 +1,453 @@
+// Example inputs from the puzzle texts together with their expected answers.
+// Answers are kept as the strings the solvers' results format to, since the
+// result types differ between days.
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Example {
+    pub input: &'static str,
+    pub part1: Option<&'static str>,
+    pub part2: Option<&'static str>,
+    // Values the examples use in place of the constants in the real puzzle,
+    // e.g. day 7 runs with 2 workers and no additional step time
+    pub parameters: &'static [(&'static str, usize)]
+}
+
+impl Example {
+    pub fn parameter(&self, name: &str) -> Option<usize> {
+        self.parameters
+            .iter()
+            .find(|&&(key, _)| key == name)
+            .map(|&(_, value)| value)
+    }
+}
+
+fn example(input: &'static str, part1: Option<&'static str>, part2: Option<&'static str>) -> Example {
+    Example { input, part1, part2, parameters: &[] }
+}
+
+const DAY10_MESSAGE: &str = concat!(
+    "\n",
+    "#   #  ###\n",
+    "#   #   # \n",
+    "#   #   # \n",
+    "#####   # \n",
+    "#   #   # \n",
+    "#   #   # \n",
+    "#   #   # \n",
+    "#   #  ###\n"
+);
+
+pub fn examples(day: usize) -> Vec<Example> {
+    match day {
+        1 => vec![
+            example("+1\n+1\n+1", Some("3"), None),
+            example("+1\n+1\n-2", Some("0"), None),
+            example("-1\n-2\n-3", Some("-6"), None),
+            example("+1\n-2\n+3\n+1", Some("3"), Some("2")),
+            example("+1\n-1", None, Some("0")),
+            example("+3\n+3\n+4\n-2\n-4", None, Some("10")),
+            example("-6\n+3\n+8\n+5\n-6", None, Some("5")),
+            example("+7\n+7\n-2\n-7\n-4", None, Some("14"))
+        ],
+        2 => vec![
+            example("abcdef
+bababc
+abbcde
+abcccd
+aabcdd
+abcdee
+ababab", Some("12"), None),
+            example("abcde
+fghij
+klmno
+pqrst
+fguij
+axcye
+wvxyz", None, Some("fgij"))
+        ],
+        3 => vec![
+            example("#1 @ 1,3: 4x4
+#2 @ 3,1: 4x4
+#3 @ 5,5: 2x2", Some("4"), Some("3"))
+        ],
+        4 => vec![
+            example("[1518-11-01 00:00] Guard #10 begins shift
+[1518-11-01 00:05] falls asleep
+[1518-11-01 00:25] wakes up
+[1518-11-01 00:30] falls asleep
+[1518-11-01 00:55] wakes up
+[1518-11-01 23:58] Guard #99 begins shift
+[1518-11-02 00:40] falls asleep
+[1518-11-02 00:50] wakes up
+[1518-11-03 00:05] Guard #10 begins shift
+[1518-11-03 00:24] falls asleep
+[1518-11-03 00:29] wakes up
+[1518-11-04 00:02] Guard #99 begins shift
+[1518-11-04 00:36] falls asleep
+[1518-11-04 00:46] wakes up
+[1518-11-05 00:03] Guard #99 begins shift
+[1518-11-05 00:45] falls asleep
+[1518-11-05 00:55] wakes up", Some("240"), Some("4455"))
+        ],
+        5 => vec![
+            example("dabAcCaCBAcCcaDA", Some("10"), Some("4"))
+        ],
+        6 => vec![
+            Example {
+                input: "1, 1
+1, 6
+8, 3
+3, 4
+5, 5
+8, 9",
+                part1: Some("17"),
+                part2: Some("16"),
+                parameters: &[("max_total_distance", 32)]
+            }
+        ],
+        7 => vec![
+            Example {
+                input: "Step C must be finished before step A can begin.
+Step C must be finished before step F can begin.
+Step A must be finished before step B can begin.
+Step A must be finished before step D can begin.
+Step B must be finished before step E can begin.
+Step D must be finished before step E can begin.
+Step F must be finished before step E can begin.",
+                part1: Some("CABDFE"),
+                part2: Some("15"),
+                parameters: &[("concurrency", 2), ("additional", 0)]
+            }
+        ],
+        8 => vec![
+            example("2 3 0 3 10 11 12 1 1 0 1 99 2 1 1 2", Some("138"), Some("66"))
+        ],
+        9 => vec![
+            example("9 players; last marble is worth 25 points", Some("32"), None),
+            example("10 players; last marble is worth 1618 points", Some("8317"), None),
+            example("13 players; last marble is worth 7999 points", Some("146373"), None),
+            example("17 players; last marble is worth 1104 points", Some("2764"), None),
+            example("21 players; last marble is worth 6111 points", Some("54718"), None),
+            example("30 players; last marble is worth 5807 points", Some("37305"), None)
+        ],
+        10 => vec![
+            example("position=< 9,  1> velocity=< 0,  2>
+position=< 7,  0> velocity=<-1,  0>
+position=< 3, -2> velocity=<-1,  1>
+position=< 6, 10> velocity=<-2, -1>
+position=< 2, -4> velocity=< 2,  2>
+position=<-6, 10> velocity=< 2, -2>
+position=< 1,  8> velocity=< 1, -1>
+position=< 1,  7> velocity=< 1,  0>
+position=<-3, 11> velocity=< 1, -2>
+position=< 7,  6> velocity=<-1, -1>
+position=<-2,  3> velocity=< 1,  0>
+position=<-4,  3> velocity=< 2,  0>
+position=<10, -3> velocity=<-1,  1>
+position=< 5, 11> velocity=< 1, -2>
+position=< 4,  7> velocity=< 0, -1>
+position=< 8, -2> velocity=< 0,  1>
+position=<15,  0> velocity=<-2,  0>
+position=< 1,  6> velocity=< 1,  0>
+position=< 8,  9> velocity=< 0, -1>
+position=< 3,  3> velocity=<-1,  1>
+position=< 0,  5> velocity=< 0, -1>
+position=<-2,  2> velocity=< 2,  0>
+position=< 5, -2> velocity=< 1,  2>
+position=< 1,  4> velocity=< 2,  1>
+position=<-2,  7> velocity=< 2, -2>
+position=< 3,  6> velocity=<-1, -1>
+position=< 5,  0> velocity=< 1,  0>
+position=<-6,  0> velocity=< 2,  0>
+position=< 5,  9> velocity=< 1, -2>
+position=<14,  7> velocity=<-2,  0>
+position=<-3,  6> velocity=< 2, -1>", Some(DAY10_MESSAGE), Some("3"))
+        ],
+        11 => vec![
+            example("18", Some("33,45"), Some("90,269,16")),
+            example("42", Some("21,61"), Some("232,251,12"))
+        ],
+        12 => vec![
+            example("initial state: #..#.#..##......###...###
+
+...## => #
+..#.. => #
+.#... => #
+.#.#. => #
+.#.## => #
+.##.. => #
+.#### => #
+#.#.# => #
+#.### => #
+##.#. => #
+##.## => #
+###.. => #
+###.# => #
+####. => #", Some("325"), None)
+        ],
+        13 => vec![
+            example(concat!(
+                "/->-\\        \n",
+                "|   |  /----\\\n",
+                "| /-+--+-\\  |\n",
+                "| | |  | v  |\n",
+                "\\-+-/  \\-+--/\n",
+                "  \\------/   \n"
+            ), Some("7,3"), None),
+            example(concat!(
+                "/>-<\\  \n",
+                "|   |  \n",
+                "| /<+-\\\n",
+                "| | | v\n",
+                "\\>+</ |\n",
+                "  |   ^\n",
+                "  \\<->/"
+            ), None, Some("6,4"))
+        ],
+        14 => vec![
+            example("9", Some("5158916779"), None),
+            example("5", Some("0124515891"), None),
+            example("18", Some("9251071085"), None),
+            example("2018", Some("5941429882"), None),
+            example("51589", None, Some("9")),
+            example("92510", None, Some("18")),
+            example("59414", None, Some("2018"))
+        ],
+        15 => vec![
+            example("#######
+#.G...#
+#...EG#
+#.#.#G#
+#..G#E#
+#.....#
+#######", Some("27730"), Some("4988")),
+            example("#######
+#G..#E#
+#E#E.E#
+#G.##.#
+#...#E#
+#...E.#
+#######", Some("36334"), None),
+            example("#########
+#G......#
+#.E.#...#
+#..##..G#
+#...##..#
+#...#...#
+#.G...G.#
+#.....G.#
+#########", Some("18740"), Some("1140"))
+        ],
+        16 => vec![
+            example("Before: [3, 2, 1, 1]
+9 2 1 2
+After:  [3, 2, 2, 1]", Some("1"), None)
+        ],
+        17 => vec![
+            example("x=495, y=2..7
+y=7, x=495..501
+x=501, y=3..7
+x=498, y=2..4
+x=506, y=1..2
+x=498, y=10..13
+x=504, y=10..13
+y=13, x=498..504", Some("57"), Some("29"))
+        ],
+        18 => vec![
+            example(".#.#...|#.
+.....#|##|
+.|..|...#.
+..|#.....#
+#.#|||#|#|
+...#.||...
+.|....|...
+||...#|.#|
+|.||||..|.
+...#.|..|.", Some("1147"), None)
+        ],
+        19 => vec![
+            example("#ip 0
+seti 5 0 1
+seti 6 0 2
+addi 0 1 0
+addr 1 2 3
+setr 1 0 0
+seti 8 0 4
+seti 9 0 5", Some("7"), None)
+        ],
+        20 => vec![
+            example("^WNE$", Some("3"), None),
+            example("^WNE(E|N)$", Some("4"), None),
+            example("^ENWWW(NEEE|SSE(EE|N))$", Some("10"), None),
+            example("^ESSWWN(E|NNENN(EESS(WNSE|)SSS|WWWSSSSE(SW|NNNE)))$", Some("23"), None),
+            example("^WSSEESWWWNW(S|NENNEEEENN(ESSSSW(NWSW|SSEN)|WSWWN(E|WWS(E|SS))))$", Some("31"), None),
+            example("^ENNWSWW(NEWS|)SSSEEN(WNSE|)EE(SWEN|)NNN$", Some("18"), None)
+        ],
+        22 => vec![
+            example("depth: 510
+target: 10,10", Some("114"), Some("45"))
+        ],
+        23 => vec![
+            example("pos=<0,0,0>, r=4
+pos=<1,0,0>, r=1
+pos=<4,0,0>, r=3
+pos=<0,2,0>, r=1
+pos=<0,5,0>, r=3
+pos=<0,0,3>, r=1
+pos=<1,1,1>, r=1
+pos=<1,1,2>, r=1
+pos=<1,3,1>, r=1", Some("7"), None),
+            example("pos=<10,12,12>, r=2
+pos=<12,14,12>, r=2
+pos=<16,12,12>, r=4
+pos=<14,14,14>, r=6
+pos=<50,50,50>, r=200
+pos=<10,10,10>, r=5", None, Some("36"))
+        ],
+        24 => vec![
+            example("Immune System:
+17 units each with 5390 hit points (weak to radiation, bludgeoning) with an attack that does 4507 fire damage at initiative 2
+989 units each with 1274 hit points (immune to fire; weak to bludgeoning, slashing) with an attack that does 25 slashing damage at initiative 3
+
+Infection:
+801 units each with 4706 hit points (weak to radiation) with an attack that does 116 bludgeoning damage at initiative 1
+4485 units each with 2961 hit points (immune to radiation; weak to fire, cold) with an attack that does 12 slashing damage at initiative 4", Some("5216"), Some("51"))
+        ],
+        25 => vec![
+            example("0,0,0,0
+ 3,0,0,0
+ 0,3,0,0
+ 0,0,3,0
+ 0,0,0,3
+ 0,0,0,6
+ 9,0,0,0
+12,0,0,0", Some("2"), None),
+            example("-1,2,2,0
+0,0,2,-2
+0,0,0,-2
+-1,2,0,0
+-2,-2,-2,2
+3,0,2,-1
+-1,3,2,2
+-1,0,-1,0
+0,2,1,-2
+3,0,0,0", Some("4"), None),
+            example("1,-1,0,1
+2,0,-1,0
+3,2,-1,0
+0,0,3,1
+0,0,-1,-1
+2,3,-2,0
+-2,2,0,0
+2,-2,0,-1
+1,-1,0,-1
+3,2,0,2", Some("3"), None),
+            example("1,-1,-1,-2
+-2,-2,0,1
+0,2,1,3
+-2,3,-2,1
+0,2,3,-2
+-1,-1,1,-2
+0,-2,-1,0
+-2,2,3,-1
+1,2,2,0
+-1,-2,0,-2", Some("8"), None)
+        ],
+        _ => vec![]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{examples, Example};
+    use {day1, day2, day3, day4, day5, day6, day7, day8, day9, day10, day11, day12, day13,
+         day14, day15, day16, day17, day18, day19, day20, day22, day24, day25};
+
+    // Generates the input anew for each part, since some solvers (day 10)
+    // advance the parsed state in place
+    macro_rules! check {
+        ($example:expr, $generator:expr, $part1:expr) => {{
+            let example: &Example = $example;
+            assert!(example.part2.is_none());
+
+            if let Some(expected) = example.part1 {
+                let input = $generator(example.input);
+                assert_eq!(format!("{}", $part1(&input)), expected, "part 1 of {:?}", example.input);
+            }
+        }};
+        ($example:expr, $generator:expr, $part1:expr, $part2:expr) => {{
+            let example: &Example = $example;
+
+            if let Some(expected) = example.part1 {
+                let input = $generator(example.input);
+                assert_eq!(format!("{}", $part1(&input)), expected, "part 1 of {:?}", example.input);
+            }
+
+            if let Some(expected) = example.part2 {
+                let input = $generator(example.input);
+                assert_eq!(format!("{}", $part2(&input)), expected, "part 2 of {:?}", example.input);
+            }
+        }};
+    }
+
+    fn text(input: &str) -> &str {
+        input
+    }
+
+    fn check(day: usize, example: &Example) {
+        match day {
+            1 => check!(example, day1::input_generator, day1::solve_part1, day1::solve_part2),
+            2 => check!(example, day2::input_generator, day2::solve_part1, day2::solve_part2),
+            3 => check!(example, day3::input_generator, day3::solve_part1, day3::solve_part2),
+            4 => check!(example, day4::input_generator, day4::solve_part1, day4::solve_part2),
+            5 => check!(example, text, day5::solve_part1, day5::solve_part2),
+            6 => {
+                let max_total_distance = example.parameter("max_total_distance").unwrap();
+                check!(example, day6::input_generator, day6::solve_part1,
+                       |input: &Vec<day6::Point>| day6::inner_part2(input, max_total_distance))
+            },
+            7 => {
+                let concurrency = example.parameter("concurrency").unwrap();
+                let additional = example.parameter("additional").unwrap();
+                check!(example, day7::input_generator, day7::solve_part1,
+                       |input: &Box<day7::Requirements>| day7::inner_part2(input, concurrency, additional))
+            },
+            8 => check!(example, day8::input_generator, day8::solve_part1, day8::solve_part2),
+            9 => check!(example, day9::input_generator, day9::solve_part1, day9::solve_part2),
+            10 => check!(example, day10::input_generator, day10::solve_part1, day10::solve_part2),
+            11 => check!(example, text, day11::solve_part1, day11::solve_part2),
+            12 => check!(example, day12::input_generator, day12::solve_part1, day12::solve_part2),
+            13 => check!(example, day13::input_generator, day13::solve_part1, day13::solve_part2),
+            14 => check!(example, day14::input_generator, day14::solve_part1, day14::solve_part2),
+            15 => check!(example, day15::input_generator, day15::solve_part1, day15::solve_part2),
+            16 => check!(example, day16::input_generator, day16::solve_part1),
+            17 => check!(example, day17::input_generator, day17::solve_part1, day17::solve_part2),
+            18 => check!(example, day18::input_generator, day18::solve_part1, day18::solve_part2),
+            19 => check!(example, day19::input_generator, day19::solve_part1, day19::solve_part2),
+            20 => check!(example, text, day20::solve_part1, day20::solve_part2),
+            22 => check!(example, day22::input_generator, day22::solve_part1, day22::solve_part2),
+            24 => check!(example, day24::input_generator, day24::solve_part1, day24::solve_part2),
+            25 => check!(example, day25::input_generator, day25::solve_part1),
+            _ => panic!("No examples expected for day {}", day)
+        }
+    }
+
+    #[test]
+    fn examples_match_solvers() {
+        // Day 23 part 2 shells out to z3, day23::tests covers it
+        for day in (1..=25).filter(|&day| day != 23) {
+            for example in examples(day) {
+                check(day, &example);
+            }
+        }
+    }
+
+    #[test]
+    fn parameters() {
+        let day7 = &examples(7)[0];
+        assert_eq!(day7.parameter("concurrency"), Some(2));
+        assert_eq!(day7.parameter("additional"), Some(0));
+        assert_eq!(day7.parameter("missing"), None);
+        assert!(examples(21).is_empty());
+    }
+}
@@ -11,6 +11,10 @@ mod tests {
 extern crate aoc_runner;
 extern crate regex;
 extern crate disjoint_sets;
+extern crate rayon;
+extern crate memmap2;
+#[cfg(feature = "bigint")]
+extern crate num_bigint;
 
 #[macro_use]
 extern crate aoc_runner_derive;
@@ -42,4 +46,9 @@ pub mod day23;
 pub mod day24;
 pub mod day25;
 
+pub mod numeric;
+pub mod simulation;
+pub mod examples;
+pub mod cache;
+
 aoc_lib!{ year = 2018 }
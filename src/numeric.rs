@@ -0,0 +1,89 @@
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt;
+
+#[cfg(feature = "bigint")]
+use num_bigint::{BigInt, BigUint};
+
+// Large parameters (day 9 marbles, day 12 generations, ...) can overflow the
+// native integer types. Computations that may do so go through checked
+// arithmetic and report an Overflow instead of silently wrapping in release
+// builds. With the bigint feature, scores and sums are arbitrary precision.
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Overflow(pub &'static str);
+
+impl fmt::Display for Overflow {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Arithmetic overflow while computing {}", self.0)
+    }
+}
+
+impl Error for Overflow {}
+
+pub fn checked<T>(value: Option<T>, computing: &'static str) -> Result<T, Overflow> {
+    value.ok_or(Overflow(computing))
+}
+
+#[cfg(not(feature = "bigint"))]
+pub type Score = usize;
+#[cfg(feature = "bigint")]
+pub type Score = BigUint;
+
+#[cfg(not(feature = "bigint"))]
+pub type Sum = isize;
+#[cfg(feature = "bigint")]
+pub type Sum = BigInt;
+
+#[cfg(not(feature = "bigint"))]
+pub fn add_score(score: &Score, points: usize) -> Result<Score, Overflow> {
+    checked(score.checked_add(points), "a score")
+}
+
+#[cfg(feature = "bigint")]
+pub fn add_score(score: &Score, points: usize) -> Result<Score, Overflow> {
+    Ok(score + BigUint::from(points))
+}
+
+// sum + steps * difference
+#[cfg(not(feature = "bigint"))]
+pub fn extrapolate(sum: isize, steps: usize, difference: isize) -> Result<Sum, Overflow> {
+    let steps = checked(isize::try_from(steps).ok(), "a sum")?;
+    let additional = checked(steps.checked_mul(difference), "a sum")?;
+    checked(sum.checked_add(additional), "a sum")
+}
+
+#[cfg(feature = "bigint")]
+pub fn extrapolate(sum: isize, steps: usize, difference: isize) -> Result<Sum, Overflow> {
+    Ok(BigInt::from(sum) + BigInt::from(steps) * BigInt::from(difference))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{add_score, checked, extrapolate, Overflow, Score, Sum};
+
+    #[test]
+    fn overflow() {
+        assert_eq!(checked(2usize.checked_mul(3), "a product"), Ok(6));
+        assert_eq!(checked(usize::max_value().checked_add(1), "a product"), Err(Overflow("a product")));
+        assert_eq!(format!("{}", Overflow("a sum")), "Arithmetic overflow while computing a sum");
+
+        assert_eq!(add_score(&Score::from(3usize), 4), Ok(Score::from(7usize)));
+        assert_eq!(extrapolate(10, 5, -3), Ok(Sum::from(-5isize)));
+    }
+
+    #[cfg(not(feature = "bigint"))]
+    #[test]
+    fn native_limits() {
+        assert!(add_score(&usize::max_value(), 1).is_err());
+        assert!(extrapolate(0, 50000000000, isize::max_value() / 1000).is_err());
+    }
+
+    #[cfg(feature = "bigint")]
+    #[test]
+    fn big_limits() {
+        let big = add_score(&Score::from(usize::max_value()), 1).unwrap();
+        assert_eq!(format!("{}", big), "18446744073709551616");
+        assert!(extrapolate(0, 50000000000, isize::max_value() / 1000).is_ok());
+    }
+}
@@ -0,0 +1,82 @@
+// Shared interface for the days that advance some state step by step until a
+// condition holds, so they can be driven from outside their solvers.
+
+pub trait Simulation {
+    type State: ?Sized;
+
+    fn step(&mut self);
+    fn is_finished(&self) -> bool;
+    fn state(&self) -> &Self::State;
+
+    // Steps until finished, returns the number of steps taken. Never returns
+    // for a simulation that doesn't finish.
+    fn run(&mut self) -> usize {
+        let mut steps = 0;
+
+        while !self.is_finished() {
+            self.step();
+            steps += 1;
+        }
+
+        steps
+    }
+
+    // Steps at most n times, stopping early if finished
+    fn run_steps(&mut self, n: usize) -> usize {
+        let mut steps = 0;
+
+        while steps < n && !self.is_finished() {
+            self.step();
+            steps += 1;
+        }
+
+        steps
+    }
+
+    // Steps until the predicate holds for the state, returns the number of
+    // steps taken or None if the simulation finished first
+    fn run_until<F: FnMut(&Self::State) -> bool>(&mut self, mut predicate: F) -> Option<usize> {
+        let mut steps = 0;
+
+        loop {
+            if predicate(self.state()) { return Some(steps) }
+            if self.is_finished() { return None }
+
+            self.step();
+            steps += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Simulation;
+
+    struct Counter {
+        count: usize,
+        limit: usize
+    }
+
+    impl Simulation for Counter {
+        type State = usize;
+
+        fn step(&mut self) { self.count += 1 }
+        fn is_finished(&self) -> bool { self.count >= self.limit }
+        fn state(&self) -> &usize { &self.count }
+    }
+
+    #[test]
+    fn drivers() {
+        assert_eq!(Counter { count: 0, limit: 5 }.run(), 5);
+
+        let mut counter = Counter { count: 0, limit: 5 };
+        assert_eq!(counter.run_steps(3), 3);
+        assert_eq!(counter.run_steps(3), 2);
+        assert!(counter.is_finished());
+
+        let mut counter = Counter { count: 0, limit: 5 };
+        assert_eq!(counter.run_until(|&count| count == 4), Some(4));
+        assert_eq!(counter.run_until(|&count| count == 7), None);
+        assert_eq!(*counter.state(), 5);
+    }
+}